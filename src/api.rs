@@ -1,6 +1,7 @@
-use crate::types::{Router, Service, TraefikData};
+use crate::types::{EntryPoint, Middleware, ParseWarning, Router, Service, ServersTransport, TcpServersTransport, TraefikData};
 use anyhow::{Context, Result};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use std::time::Duration;
 
 pub struct TraefikClient {
@@ -8,6 +9,57 @@ pub struct TraefikClient {
     base_url: String,
 }
 
+/// Parse a JSON array entry by entry, so one malformed entry doesn't drop the whole list
+pub fn parse_entries<T: DeserializeOwned>(kind: &str, text: &str) -> Result<(Vec<T>, Vec<ParseWarning>)> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(text)
+        .with_context(|| format!("Failed to parse {} JSON", kind))?;
+
+    let mut entries = Vec::with_capacity(values.len());
+    let mut warnings = Vec::new();
+
+    for (index, value) in values.into_iter().enumerate() {
+        let name = value
+            .get("name")
+            .and_then(|name| name.as_str())
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("#{}", index));
+
+        match serde_json::from_value::<T>(value) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => warnings.push(ParseWarning {
+                kind: kind.to_string(),
+                name,
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    Ok((entries, warnings))
+}
+
+/// Unwrap a supplementary list, turning a failed fetch into a warning; None when the list is unavailable
+fn optional_entries<T>(
+    kind: &str,
+    result: Result<Option<(Vec<T>, Vec<ParseWarning>)>>,
+    warnings: &mut Vec<ParseWarning>,
+) -> Option<Vec<T>> {
+    match result {
+        Ok(Some((entries, entry_warnings))) => {
+            warnings.extend(entry_warnings);
+            Some(entries)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warnings.push(ParseWarning {
+                kind: kind.to_string(),
+                name: "*".to_string(),
+                message: format!("{:#}", e),
+            });
+            None
+        }
+    }
+}
+
 impl TraefikClient {
     pub fn new(base_url: String, insecure: bool) -> Result<Self> {
         let mut builder = Client::builder()
@@ -23,7 +75,7 @@ impl TraefikClient {
         Ok(Self { client, base_url })
     }
 
-    pub async fn get_routers(&self) -> Result<Vec<Router>> {
+    pub async fn get_routers(&self) -> Result<(Vec<Router>, Vec<ParseWarning>)> {
         let url = format!("{}/api/http/routers", self.base_url);
         let response = self
             .client
@@ -42,13 +94,10 @@ impl TraefikClient {
         }
 
         let text = response.text().await.context("Failed to get response text")?;
-        let routers: Vec<Router> = serde_json::from_str(&text)
-            .context("Failed to parse routers JSON")?;
-
-        Ok(routers)
+        parse_entries("router", &text)
     }
 
-    pub async fn get_services(&self) -> Result<Vec<Service>> {
+    pub async fn get_services(&self) -> Result<(Vec<Service>, Vec<ParseWarning>)> {
         let url = format!("{}/api/http/services", self.base_url);
         let response = self
             .client
@@ -64,21 +113,84 @@ impl TraefikClient {
             ));
         }
 
-        let services: Vec<Service> = response
-            .json()
+        let text = response.text().await.context("Failed to get response text")?;
+        parse_entries("service", &text)
+    }
+
+    /// Fetch a list that older Traefik versions don't serve; a 404 yields None
+    async fn get_optional_list<T: DeserializeOwned>(&self, path: &str, kind: &str) -> Result<Option<(Vec<T>, Vec<ParseWarning>)>> {
+        let url = format!("{}{}", self.base_url, path);
+        let response = self
+            .client
+            .get(&url)
+            .send()
             .await
-            .context("Failed to parse services JSON")?;
+            .context("Failed to send request to Traefik API")?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Failed to fetch {}: HTTP {}",
+                path,
+                response.status()
+            ));
+        }
 
-        Ok(services)
+        let text = response.text().await.context("Failed to get response text")?;
+        parse_entries(kind, &text).map(Some)
     }
 
+    pub async fn get_middlewares(&self) -> Result<Option<(Vec<Middleware>, Vec<ParseWarning>)>> {
+        self.get_optional_list("/api/http/middlewares", "middleware").await
+    }
 
-    pub async fn fetch_all_data(&self) -> Result<TraefikData> {
-        let (routers_result, services_result) = tokio::join!(self.get_routers(), self.get_services());
+    pub async fn get_entry_points(&self) -> Result<Option<(Vec<EntryPoint>, Vec<ParseWarning>)>> {
+        self.get_optional_list("/api/entrypoints", "entrypoint").await
+    }
 
-        let routers = routers_result.context("Failed to fetch routers")?;
-        let services = services_result.context("Failed to fetch services")?;
+    /// HTTP serversTransports (Traefik v3)
+    pub async fn get_servers_transports(&self) -> Result<Option<(Vec<ServersTransport>, Vec<ParseWarning>)>> {
+        self.get_optional_list("/api/http/serversTransports", "serversTransport").await
+    }
 
-        Ok(TraefikData { routers, services })
+    /// TCP serversTransports (Traefik v3)
+    pub async fn get_tcp_servers_transports(&self) -> Result<Option<(Vec<TcpServersTransport>, Vec<ParseWarning>)>> {
+        self.get_optional_list("/api/tcp/serversTransports", "tcp serversTransport").await
     }
-}
\ No newline at end of file
+
+    pub async fn fetch_all_data(&self) -> Result<TraefikData> {
+        let (routers_result, services_result, middlewares_result, entry_points_result, transports_result, tcp_transports_result) = tokio::join!(
+            self.get_routers(),
+            self.get_services(),
+            self.get_middlewares(),
+            self.get_entry_points(),
+            self.get_servers_transports(),
+            self.get_tcp_servers_transports()
+        );
+
+        let (routers, mut warnings) = routers_result.context("Failed to fetch routers")?;
+        let (services, service_warnings) = services_result.context("Failed to fetch services")?;
+        warnings.extend(service_warnings);
+
+        // Middlewares, entrypoints and transports are supplementary, a failure is reported but doesn't drop the snapshot
+        let middlewares = optional_entries("middleware", middlewares_result, &mut warnings);
+        let entry_points = optional_entries("entrypoint", entry_points_result, &mut warnings).unwrap_or_default();
+        let servers_transports = optional_entries("serversTransport", transports_result, &mut warnings).unwrap_or_default();
+        let tcp_servers_transports =
+            optional_entries("tcp serversTransport", tcp_transports_result, &mut warnings).unwrap_or_default();
+
+        Ok(TraefikData {
+            routers,
+            services,
+            middlewares_available: middlewares.is_some(),
+            middlewares: middlewares.unwrap_or_default(),
+            entry_points,
+            servers_transports,
+            tcp_servers_transports,
+            warnings,
+        })
+    }
+}
@@ -1,7 +1,25 @@
 use crate::api::TraefikClient;
-use crate::service_status::{get_router_status_info, get_failover_services, get_service_status, ServiceStatus};
-use crate::types::{Router, Service, TraefikData};
-use std::collections::HashSet;
+use crate::audit::{audit, AuditConfig, AuditFinding, AuditRule};
+use crate::certs::{load_acme_json, now_timestamp, router_cert_status, tls_domains, CertStatus, StoredCert};
+use crate::docker::{compose_service, find_container, Container, DockerClient};
+use crate::grouping::{build_rows, group_routers, router_hosts, router_provider, GroupContext, GroupMode, Row, RouterGroup};
+use crate::kubernetes::parse_kube_name;
+use crate::lint::{lint, LintFinding, Severity};
+use crate::migrate::{migrations, migrations_yaml};
+use crate::probe::{probe_all, probe_servers, ProbeResult, ServerProbe};
+use crate::redirects::{pair_redirects, HostRedirect, PairStatus};
+use crate::route_analysis::{analyze_routes, RouteWarning};
+use crate::route_match::{effective_priority, route_destination, test_route, RouteRequest};
+use crate::rule::{parse_router_rule, tokenize, Matcher, TokenKind};
+use crate::service_status::{
+    build_service_tree, find_servers_transport, get_router_status_info, has_down_server, servers_transport_name,
+    NodeKind, NodeRole, ServiceNode, ServiceStatus,
+};
+use crate::tls_inspect::{connector, inspect_all, EntryPointAddress, ServedCert, ServedStatus};
+use crate::types::{
+    HealthCheck, ParseWarning, Router, ServersTransport, Service, Sticky, TcpServersTransport, TlsConfig, TraefikData,
+    TransportCertificate,
+};
 use anyhow::Result;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -10,13 +28,18 @@ use ratatui::{
     widgets::{ListState, Paragraph},
     Frame,
 };
+use std::collections::HashSet;
+use std::future::Future;
+use std::io::Write;
 use std::time::{Duration, Instant};
+use tokio::sync::oneshot::{self, error::TryRecvError};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppState {
     Normal,
     Search,
     Filtered, // New state: search query is committed and filtering is active
+    RouteInput, // Typing a request for the route tester
     Loading,
     Error(String),
 }
@@ -25,6 +48,23 @@ pub enum AppState {
 pub enum SortMode {
     Dead,  // Dead services first
     Name,  // Alphabetical by name
+    Priority, // Effective priority within each entrypoint set
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum View {
+    Routers,
+    ServiceDetail(String), // Service name
+    ParseWarnings,
+    ServersTransports,
+    RouteTest,
+    RouteConflicts,
+    Migration,
+    Services,
+    Lint,
+    Audit,
+    Redirects,
+    Certificates,
 }
 
 pub struct App {
@@ -39,9 +79,68 @@ pub struct App {
     pub refresh_interval: Duration,
     pub sort_mode: SortMode,
     pub scroll_offset: usize,
-    pub selected_router_index: usize,
+    /// Index into `rows`
+    pub selected_row: usize,
+    pub group_mode: GroupMode,
+    pub groups: Vec<RouterGroup>,
+    /// What the router list shows: group headers and routers
+    pub rows: Vec<Row>,
+    /// Names of the groups showing only their header
+    pub collapsed_groups: HashSet<String>,
+    pub hide_internal: bool,
+    /// Namespaces that help split Kubernetes router names, which may contain dashes
+    pub namespace_hints: Vec<String>,
+    /// Set when a Docker socket is configured to enrich docker provider routers
+    pub docker: Option<DockerClient>,
+    pub containers: Vec<Container>,
     pub pending_g_key: bool,
     pub ignore_patterns: Vec<String>,
+    pub view: View,
+    pub view_scroll: usize,
+    pub route_input: String,
+    pub route_request: Option<Result<RouteRequest, String>>,
+    pub route_warnings: Vec<RouteWarning>,
+    pub lint_findings: Vec<LintFinding>,
+    pub audit_config: AuditConfig,
+    pub audit_findings: Vec<AuditFinding>,
+    pub redirect_pairs: Vec<HostRedirect>,
+    /// acme.json files to read certificates from, certificate checks are off without any
+    pub acme_paths: Vec<String>,
+    /// Certificates expiring within this many days are flagged
+    pub cert_warn_days: i64,
+    pub certificates: Vec<StoredCert>,
+    /// Resolvers found in the acme.json files
+    pub acme_resolvers: Vec<String>,
+    /// Entrypoints to handshake with for every TLS host, live inspection is off without any
+    pub tls_addresses: Vec<EntryPointAddress>,
+    pub served_certs: Vec<ServedCert>,
+    /// Handshakes running in the background
+    tls_inspection: Option<oneshot::Receiver<Vec<ServedCert>>>,
+    /// Entrypoints to send synthetic requests through for every router, probing is off without any
+    pub probe_addresses: Vec<EntryPointAddress>,
+    pub probe_results: Vec<ProbeResult>,
+    /// Router probes running in the background
+    router_probing: Option<oneshot::Receiver<Vec<ProbeResult>>>,
+    /// How often to request every load balancer server directly, off when None
+    pub server_probe_interval: Option<Duration>,
+    pub server_probes: Vec<ServerProbe>,
+    pub last_server_probe: Option<Instant>,
+    /// Server probes running in the background
+    server_probing: Option<oneshot::Receiver<Vec<ServerProbe>>>,
+    /// Outcome of the last action, shown in red when it failed
+    pub status_message: Option<Result<String, String>>,
+    /// Where `x` writes the suggested v3 rules, a timestamped file in the working directory when None
+    pub migration_export_path: Option<String>,
+    /// Print the selected router's rule over several lines
+    pub rule_pretty: bool,
+    /// Horizontal scroll of the selected router's rule, in characters
+    pub rule_scroll: usize,
+    /// Index into `listed_services()`
+    pub selected_service: usize,
+    /// Only list services with at least one DOWN server
+    pub services_down_only: bool,
+    /// The router list is filtered to a service picked in the services view, Esc goes back there
+    pub return_to_services: bool,
 }
 
 impl App {
@@ -62,9 +161,47 @@ impl App {
             refresh_interval: Duration::from_secs(30),
             sort_mode: SortMode::Dead, // Default to dead services first
             scroll_offset: 0,
-            selected_router_index: 0,
+            selected_row: 0,
+            group_mode: GroupMode::None,
+            groups: Vec::new(),
+            rows: Vec::new(),
+            collapsed_groups: HashSet::new(),
+            hide_internal: false,
+            namespace_hints: Vec::new(),
+            docker: None,
+            containers: Vec::new(),
             pending_g_key: false,
             ignore_patterns,
+            view: View::Routers,
+            view_scroll: 0,
+            route_input: String::new(),
+            route_request: None,
+            route_warnings: Vec::new(),
+            lint_findings: Vec::new(),
+            audit_config: AuditConfig::default(),
+            audit_findings: Vec::new(),
+            redirect_pairs: Vec::new(),
+            acme_paths: Vec::new(),
+            cert_warn_days: 14,
+            certificates: Vec::new(),
+            acme_resolvers: Vec::new(),
+            tls_addresses: Vec::new(),
+            served_certs: Vec::new(),
+            tls_inspection: None,
+            probe_addresses: Vec::new(),
+            probe_results: Vec::new(),
+            router_probing: None,
+            server_probe_interval: None,
+            server_probes: Vec::new(),
+            last_server_probe: None,
+            server_probing: None,
+            status_message: None,
+            migration_export_path: None,
+            rule_pretty: false,
+            rule_scroll: 0,
+            selected_service: 0,
+            services_down_only: false,
+            return_to_services: false,
         })
     }
 
@@ -76,7 +213,74 @@ impl App {
         self.last_update = Some(Instant::now());
         
         match result {
-            Ok(data) => {
+            Ok(mut data) => {
+                // Container details are supplementary, a failure is reported like a skipped entry
+                if let Some(ref docker) = self.docker {
+                    match docker.list_containers().await {
+                        Ok(containers) => self.containers = containers,
+                        Err(e) => {
+                            self.containers.clear();
+                            data.warnings.push(ParseWarning {
+                                kind: "docker".to_string(),
+                                name: "*".to_string(),
+                                message: format!("{:#}", e),
+                            });
+                        }
+                    }
+                }
+
+                // Certificates are read on every refresh, the resolvers renew them in place
+                self.certificates.clear();
+                self.acme_resolvers.clear();
+                for path in &self.acme_paths {
+                    match load_acme_json(path) {
+                        Ok((store, warnings)) => {
+                            self.certificates.extend(store.certs);
+                            self.acme_resolvers.extend(store.resolvers);
+                            data.warnings.extend(warnings);
+                        }
+                        Err(e) => data.warnings.push(ParseWarning {
+                            kind: "acme".to_string(),
+                            name: path.clone(),
+                            message: format!("{:#}", e),
+                        }),
+                    }
+                }
+                self.certificates.sort_by_key(|stored| stored.cert.not_after);
+
+                // Handshakes run in the background, one inspection at a time
+                if !self.tls_addresses.is_empty() && self.tls_inspection.is_none() {
+                    match connector() {
+                        Ok(connector) => {
+                            let (routers, entry_points) = (data.routers.clone(), data.entry_points.clone());
+                            let addresses = self.tls_addresses.clone();
+                            self.tls_inspection = Some(spawn_task(async move {
+                                inspect_all(connector, &routers, &entry_points, &addresses).await
+                            }));
+                        }
+                        Err(e) => {
+                            self.served_certs.clear();
+                            data.warnings.push(ParseWarning {
+                                kind: "tls".to_string(),
+                                name: "*".to_string(),
+                                message: format!("{:#}", e),
+                            });
+                        }
+                    }
+                }
+
+                // The previous results stay until the new round is done
+                if !self.probe_addresses.is_empty() && self.router_probing.is_none() {
+                    let (routers, entry_points) = (data.routers.clone(), data.entry_points.clone());
+                    let addresses = self.probe_addresses.clone();
+                    self.router_probing =
+                        Some(spawn_task(async move { probe_all(&routers, &entry_points, &addresses).await }));
+                }
+
+                self.route_warnings = analyze_routes(&data.routers);
+                self.lint_findings = lint(&data);
+                self.audit_findings = audit(&data, &self.audit_config);
+                self.redirect_pairs = pair_redirects(&data);
                 self.traefik_data = Some(data);
                 self.update_filtered_routers();
                 // Only change state if we're currently loading
@@ -96,6 +300,40 @@ impl App {
         Ok(())
     }
 
+    /// Whether the servers are due for another direct probe, and the last one is done
+    pub fn server_probe_due(&self) -> bool {
+        if self.server_probing.is_some() {
+            return false;
+        }
+        match (self.server_probe_interval, self.last_server_probe) {
+            (None, _) => false,
+            (Some(_), None) => self.traefik_data.is_some(),
+            (Some(interval), Some(last_probe)) => last_probe.elapsed() >= interval,
+        }
+    }
+
+    /// Probe the servers in the background, the current results stay until `collect_background` takes the new ones
+    pub fn start_server_probes(&mut self) {
+        self.last_server_probe = Some(Instant::now());
+        if let Some(ref data) = self.traefik_data {
+            let services = data.services.clone();
+            self.server_probing = Some(spawn_task(async move { probe_servers(&services).await }));
+        }
+    }
+
+    /// Take the results of background work that has finished
+    pub fn collect_background(&mut self) {
+        if let Some(served) = poll_task(&mut self.tls_inspection) {
+            self.served_certs = served;
+        }
+        if let Some(results) = poll_task(&mut self.router_probing) {
+            self.probe_results = results;
+        }
+        if let Some(probes) = poll_task(&mut self.server_probing) {
+            self.server_probes = probes;
+        }
+    }
+
     pub fn update_filtered_routers(&mut self) {
         self.update_filtered_routers_with_reset(false);
     }
@@ -118,9 +356,8 @@ impl App {
                     let middle = &pattern_lower[1..pattern_lower.len()-1];
                     name_lower.contains(middle)
                 }
-            } else if pattern_lower.starts_with('*') {
+            } else if let Some(suffix) = pattern_lower.strip_prefix('*') {
                 // Ends with pattern: *foo
-                let suffix = &pattern_lower[1..];
                 name_lower.ends_with(suffix)
             } else if pattern_lower.ends_with('*') {
                 // Starts with pattern: foo*
@@ -139,6 +376,60 @@ impl App {
         false
     }
 
+    /// Free text matches name, rule or service; `ns:` qualifiers match the Kubernetes namespace,
+    /// `service:` qualifiers the routers using a service
+    fn matches_search(&self, router: &Router) -> bool {
+        let mut text = Vec::new();
+        for term in self.search_query.split_whitespace() {
+            match term.split_once(':') {
+                Some(("ns", namespace)) => {
+                    let matches = parse_kube_name(router, &self.namespace_hints)
+                        .is_some_and(|kube| kube.namespace.to_lowercase().contains(&namespace.to_lowercase()));
+                    if !matches {
+                        return false;
+                    }
+                }
+                Some(("service", service)) => {
+                    if !self.router_uses_service(router, service) {
+                        return false;
+                    }
+                }
+                Some(("resolver", resolver)) => {
+                    let matches = router
+                        .tls
+                        .as_ref()
+                        .and_then(|tls| tls.cert_resolver.as_deref())
+                        .is_some_and(|name| name.to_lowercase().contains(&resolver.to_lowercase()));
+                    if !matches {
+                        return false;
+                    }
+                }
+                _ => text.push(term),
+            }
+        }
+
+        let text = text.join(" ").to_lowercase();
+        router.name.to_lowercase().contains(&text)
+            || router.rule.to_lowercase().contains(&text)
+            || router.service.to_lowercase().contains(&text)
+    }
+
+    /// Whether a router targets the named service, given with or without its provider
+    fn router_uses_service(&self, router: &Router, service_name: &str) -> bool {
+        let services = self.traefik_data.as_ref().map(|d| &d.services[..]).unwrap_or(&[]);
+        let is_named = |name: &str| {
+            name.eq_ignore_ascii_case(service_name)
+                || name.split('@').next().is_some_and(|bare| bare.eq_ignore_ascii_case(service_name))
+        };
+
+        // usedBy is what Traefik resolved, the router's own reference covers services without it
+        let listed = services
+            .iter()
+            .filter(|service| is_named(&service.name))
+            .any(|service| service.used_by.as_ref().is_some_and(|used_by| used_by.contains(&router.name)));
+        listed || self.get_service_for_router(router).is_some_and(|service| is_named(&service.name))
+    }
+
     pub fn update_filtered_routers_with_reset(&mut self, reset_position: bool) {
         if let Some(ref data) = self.traefik_data {
             // First filter by ignore patterns
@@ -146,19 +437,13 @@ impl App {
                 .routers
                 .iter()
                 .filter(|router| !self.matches_ignore_pattern(&router.name))
+                .filter(|router| !(self.hide_internal && router_provider(router) == Some("internal")))
                 .cloned()
                 .collect();
 
             // Then filter by search query
             if !self.search_query.is_empty() {
-                filtered = filtered
-                    .into_iter()
-                    .filter(|router| {
-                        router.name.to_lowercase().contains(&self.search_query.to_lowercase())
-                            || router.rule.to_lowercase().contains(&self.search_query.to_lowercase())
-                            || router.service.to_lowercase().contains(&self.search_query.to_lowercase())
-                    })
-                    .collect();
+                filtered.retain(|router| self.matches_search(router));
             }
 
             // Then sort based on sort mode
@@ -184,216 +469,180 @@ impl App {
                     // Sort alphabetically by name
                     filtered.sort_by(|a, b| a.name.cmp(&b.name));
                 }
+                SortMode::Priority => {
+                    // The order Traefik evaluates the routers in, `list_group_mode` splits it per entrypoint
+                    filtered.sort_by(|a, b| {
+                        effective_priority(b).0.cmp(&effective_priority(a).0).then_with(|| a.name.cmp(&b.name))
+                    });
+                }
             }
 
+            let context = GroupContext {
+                services: &data.services,
+                namespace_hints: &self.namespace_hints,
+                containers: &self.containers,
+            };
+            let group_mode = self.list_group_mode();
+            self.groups = group_routers(&filtered, group_mode, &context);
+            self.rows = build_rows(filtered.len(), &self.groups, group_mode, &self.collapsed_groups);
             self.filtered_routers = filtered;
 
             // Only reset position when explicitly requested (search/sort changes)
             if reset_position {
                 self.scroll_offset = 0;
-                self.selected_router_index = 0;
+                self.selected_row = 0;
             } else {
                 // Ensure selection is still valid after refresh
-                if self.selected_router_index >= self.filtered_routers.len() && !self.filtered_routers.is_empty() {
-                    self.selected_router_index = self.filtered_routers.len() - 1;
+                if self.selected_row >= self.rows.len() && !self.rows.is_empty() {
+                    self.selected_row = self.rows.len() - 1;
                 }
             }
 
             // Update list state for compatibility
-            if !self.filtered_routers.is_empty() {
-                self.list_state.select(Some(self.selected_router_index));
+            if !self.rows.is_empty() {
+                self.list_state.select(Some(self.selected_row));
             } else {
                 self.list_state.select(None);
-                self.selected_router_index = 0;
+                self.selected_row = 0;
                 self.scroll_offset = 0;
             }
         }
     }
 
+    /// The router on the selected row, None for a group header
+    pub fn selected_router(&self) -> Option<&Router> {
+        match self.rows.get(self.selected_row)? {
+            Row::Router(index) => self.filtered_routers.get(*index),
+            Row::Group(_) => None,
+        }
+    }
+
+    /// Grouping of the router list: priorities only compare within an entrypoint, so the priority sort
+    /// groups by entrypoint unless another grouping is chosen
+    fn list_group_mode(&self) -> GroupMode {
+        if self.sort_mode == SortMode::Priority && self.group_mode == GroupMode::None {
+            GroupMode::EntryPoint
+        } else {
+            self.group_mode
+        }
+    }
+
+    pub fn toggle_group_mode(&mut self) {
+        self.group_mode = self.group_mode.next();
+        self.collapsed_groups.clear();
+        self.update_filtered_routers_with_reset(true);
+    }
+
+    /// Collapse or expand the group on the selected row, returns false when a router is selected
+    pub fn toggle_selected_group(&mut self) -> bool {
+        let Some(&Row::Group(index)) = self.rows.get(self.selected_row) else {
+            return false;
+        };
+        let name = self.groups[index].name.clone();
+        if !self.collapsed_groups.remove(&name) {
+            self.collapsed_groups.insert(name);
+        }
+        // The header stays at the same row, only the rows after it change
+        self.update_filtered_routers();
+        true
+    }
+
+    pub fn toggle_hide_internal(&mut self) {
+        self.hide_internal = !self.hide_internal;
+        self.update_filtered_routers_with_reset(true);
+    }
+
+    /// Select a row, the horizontal rule scroll only applies to the router it was made on
+    fn select_router(&mut self, index: usize) {
+        if index != self.selected_row {
+            self.rule_scroll = 0;
+        }
+        self.selected_row = index;
+    }
+
     pub fn next_router(&mut self) {
-        if self.filtered_routers.is_empty() {
+        if self.rows.is_empty() {
             return;
         }
         
-        if self.selected_router_index < self.filtered_routers.len() - 1 {
-            self.selected_router_index += 1;
+        if self.selected_row < self.rows.len() - 1 {
+            self.select_router(self.selected_row + 1);
         }
     }
 
     pub fn previous_router(&mut self) {
-        if self.filtered_routers.is_empty() {
+        if self.rows.is_empty() {
             return;
         }
         
-        if self.selected_router_index > 0 {
-            self.selected_router_index -= 1;
+        if self.selected_row > 0 {
+            self.select_router(self.selected_row - 1);
         }
     }
 
     pub fn go_to_first_router(&mut self) {
-        if !self.filtered_routers.is_empty() {
-            self.selected_router_index = 0;
+        if !self.rows.is_empty() {
+            self.select_router(0);
         }
     }
 
     pub fn go_to_last_router(&mut self) {
-        if !self.filtered_routers.is_empty() {
-            self.selected_router_index = self.filtered_routers.len() - 1;
+        if !self.rows.is_empty() {
+            self.select_router(self.rows.len() - 1);
         }
     }
 
     pub fn page_down(&mut self, page_size: usize) {
-        if self.filtered_routers.is_empty() {
+        if self.rows.is_empty() {
             return;
         }
         
-        let new_index = (self.selected_router_index + page_size).min(self.filtered_routers.len() - 1);
-        self.selected_router_index = new_index;
+        let new_index = (self.selected_row + page_size).min(self.rows.len() - 1);
+        self.select_router(new_index);
     }
 
     pub fn page_up(&mut self, page_size: usize) {
-        if self.filtered_routers.is_empty() {
+        if self.rows.is_empty() {
             return;
         }
         
-        let new_index = self.selected_router_index.saturating_sub(page_size);
-        self.selected_router_index = new_index;
+        let new_index = self.selected_row.saturating_sub(page_size);
+        self.select_router(new_index);
     }
 
-    fn get_router_start_line(&self, router_index: usize) -> usize {
-        let mut line_count = 0;
-        let _all_services = self.traefik_data.as_ref().map(|d| &d.services[..]).unwrap_or(&[]);
-        
-        for i in 0..router_index {
-            if i >= self.filtered_routers.len() {
-                break;
-            }
-            
-            let router = &self.filtered_routers[i];
-            
-            // Router name line
-            line_count += 1;
-            // Rule line
-            line_count += 1;
-            
-            // Service lines
-            if let Some(main_service) = self.get_service_for_router(router) {
-                if main_service.service_type.as_deref() == Some("failover") || main_service.failover.is_some() {
-                    // Failover service line + primary + fallback lines
-                    line_count += 3;
-                } else {
-                    // Regular service line
-                    line_count += 1;
-                    // Server lines (if this router is selected)
-                    if i == self.selected_router_index {
-                        if let Some(ref lb) = main_service.load_balancer {
-                            line_count += lb.servers.len();
-                        }
-                    }
-                }
-            } else {
-                // Service not found line
-                line_count += 1;
-            }
-            
-            // Empty line separator (except for last router)
-            if i < self.filtered_routers.len() - 1 {
-                line_count += 1;
-            }
-        }
-        
-        line_count
+    pub fn toggle_rule_pretty(&mut self) {
+        self.rule_pretty = !self.rule_pretty;
+        self.rule_scroll = 0;
     }
 
-    pub fn ensure_selected_visible(&mut self, viewport_height: usize) {
-        if self.filtered_routers.is_empty() {
+    /// Scroll the selected router's rule sideways, keeping at least its last character visible
+    pub fn scroll_rule(&mut self, delta: isize) {
+        let Some(router) = self.selected_router() else {
             return;
-        }
-        
-        let router_start_line = self.get_router_start_line(self.selected_router_index);
-        let total_lines = self.calculate_total_lines();
-        
-        // Calculate how many lines this router takes
-        let router_lines = if self.selected_router_index < self.filtered_routers.len() - 1 {
-            self.get_router_start_line(self.selected_router_index + 1) - router_start_line
-        } else {
-            total_lines - router_start_line
         };
-        
-        // Ensure the selected router is visible
-        if router_start_line < self.scroll_offset {
-            // Router is above the viewport, scroll up to show it
-            self.scroll_offset = router_start_line;
-        } else if router_start_line + router_lines > self.scroll_offset + viewport_height {
-            // Router is below the viewport, scroll down to show it
-            if router_lines <= viewport_height {
-                self.scroll_offset = (router_start_line + router_lines).saturating_sub(viewport_height);
-            } else {
-                // Router is bigger than viewport, show the start
-                self.scroll_offset = router_start_line;
-            }
-        }
-        
-        // Ensure scroll_offset doesn't exceed bounds
-        if total_lines > viewport_height {
-            let max_scroll = total_lines - viewport_height;
-            if self.scroll_offset > max_scroll {
-                self.scroll_offset = max_scroll;
-            }
-        } else {
-            self.scroll_offset = 0;
-        }
+        let max_scroll = router.rule.chars().count().saturating_sub(1);
+        self.rule_scroll = self.rule_scroll.saturating_add_signed(delta).min(max_scroll);
     }
 
-    fn calculate_total_lines(&self) -> usize {
-        let mut total_lines = 0;
-        for (i, router) in self.filtered_routers.iter().enumerate() {
-            // Router name line
-            total_lines += 1;
-            // Rule line
-            total_lines += 1;
-            
-            // Service lines
-            if let Some(ref _data) = self.traefik_data {
-                if let Some(main_service) = self.get_service_for_router(router) {
-                    if main_service.service_type.as_deref() == Some("failover") || main_service.failover.is_some() {
-                        // Failover service line
-                        total_lines += 1;
-                        // Primary and fallback lines
-                        total_lines += 2;
-                    } else {
-                        // Regular service line
-                        total_lines += 1;
-                        // Server lines (if service is selected)
-                        if i == self.selected_router_index {
-                            if let Some(ref lb) = main_service.load_balancer {
-                                total_lines += lb.servers.len();
-                            }
-                        }
-                    }
-                } else {
-                    // Service not found line
-                    total_lines += 1;
-                }
-            }
-            
-            // Empty line separator (except for last router)
-            if i < self.filtered_routers.len() - 1 {
-                total_lines += 1;
-            }
-        }
-        total_lines
+    /// Adjust scroll so the selected row block is visible, given the line count of every row block
+    pub fn ensure_selected_visible(&mut self, viewport_height: usize, block_heights: &[usize]) {
+        self.scroll_offset = scroll_to_block(self.scroll_offset, self.selected_row, viewport_height, block_heights);
     }
 
 
     pub fn enter_search_mode(&mut self) {
         self.state = AppState::Search;
+        self.return_to_services = false;
     }
 
     pub fn exit_search_mode(&mut self) {
         self.state = AppState::Normal;
         self.search_query.clear();
         self.update_filtered_routers_with_reset(true); // Reset position when clearing search
+        if self.return_to_services {
+            self.return_to_services = false;
+            self.open_view(View::Services);
+        }
     }
 
     pub fn commit_search(&mut self) {
@@ -409,6 +658,31 @@ impl App {
         self.update_filtered_routers_with_reset(true); // Reset position on search change
     }
 
+    /// State to return to after a prompt, depending on whether a filter is active
+    fn idle_state(&self) -> AppState {
+        if self.search_query.is_empty() {
+            AppState::Normal
+        } else {
+            AppState::Filtered
+        }
+    }
+
+    pub fn enter_route_input(&mut self) {
+        self.route_input.clear();
+        self.state = AppState::RouteInput;
+    }
+
+    pub fn cancel_route_input(&mut self) {
+        self.state = self.idle_state();
+    }
+
+    /// Parse the typed request and show which router handles it
+    pub fn submit_route_input(&mut self) {
+        self.state = self.idle_state();
+        self.route_request = Some(RouteRequest::from_prompt(&self.route_input).map_err(|e| format!("{:#}", e)));
+        self.open_view(View::RouteTest);
+    }
+
     pub fn quit(&mut self) {
         self.should_quit = true;
     }
@@ -416,7 +690,8 @@ impl App {
     pub fn toggle_sort_mode(&mut self) {
         self.sort_mode = match self.sort_mode {
             SortMode::Dead => SortMode::Name,
-            SortMode::Name => SortMode::Dead,
+            SortMode::Name => SortMode::Priority,
+            SortMode::Priority => SortMode::Dead,
         };
         self.update_filtered_routers_with_reset(true); // Reset position on sort change
     }
@@ -427,6 +702,100 @@ impl App {
         crate::service_status::find_service_by_name(&router.service, services)
     }
 
+    /// Open the detail view for the service of the selected router
+    pub fn open_selected_service_detail(&mut self) {
+        let service_name = self
+            .selected_router()
+            .and_then(|router| self.get_service_for_router(router))
+            .map(|service| service.name.clone());
+
+        if let Some(name) = service_name {
+            self.view = View::ServiceDetail(name);
+            self.view_scroll = 0;
+        }
+    }
+
+    pub fn open_view(&mut self, view: View) {
+        self.view = view;
+        self.view_scroll = 0;
+        self.status_message = None;
+    }
+
+    pub fn close_view(&mut self) {
+        self.open_view(View::Routers);
+    }
+
+    /// Write the suggested v3 rules as file provider YAML, an existing file is never overwritten
+    pub fn export_migrations(&mut self) {
+        let Some(ref data) = self.traefik_data else {
+            return;
+        };
+        let migrations = migrations(&data.routers);
+        let path = self
+            .migration_export_path
+            .clone()
+            .unwrap_or_else(|| format!("traefiktop-v3-rules-{}.yaml", now_timestamp()));
+
+        let written = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(migrations_yaml(&migrations).as_bytes()));
+        self.status_message = Some(match written {
+            Ok(()) => Ok(format!("Exported {} rules to {}", migrations.len(), path)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Err(format!("{} already exists, not exported", path)),
+            Err(e) => Err(format!("Export to {} failed: {}", path, e)),
+        });
+    }
+
+    /// Services in the services view, by name
+    pub fn listed_services(&self) -> Vec<&Service> {
+        let Some(ref data) = self.traefik_data else {
+            return Vec::new();
+        };
+        let mut services: Vec<&Service> = data
+            .services
+            .iter()
+            .filter(|service| !(self.hide_internal && service.provider == "internal"))
+            .filter(|service| !self.services_down_only || has_down_server(service))
+            .collect();
+        services.sort_by(|a, b| a.name.cmp(&b.name));
+        services
+    }
+
+    /// Move the services view selection, staying inside the list
+    pub fn move_service_selection(&mut self, delta: isize) {
+        let last = self.listed_services().len().saturating_sub(1);
+        self.selected_service = self.selected_service.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn toggle_services_down_only(&mut self) {
+        self.services_down_only = !self.services_down_only;
+        self.selected_service = 0;
+        self.view_scroll = 0;
+    }
+
+    /// Show the routers of the selected service, Esc on the filtered list comes back here
+    pub fn open_selected_service_routers(&mut self) {
+        let Some(name) = self.listed_services().get(self.selected_service).map(|service| service.name.clone()) else {
+            return;
+        };
+        self.search_query = format!("service:{}", name);
+        self.state = AppState::Filtered;
+        self.return_to_services = true;
+        self.open_view(View::Routers);
+        self.update_filtered_routers_with_reset(true);
+    }
+
+    pub fn scroll_view_down(&mut self, lines: usize) {
+        // Clamped against the content height while rendering
+        self.view_scroll = self.view_scroll.saturating_add(lines);
+    }
+
+    pub fn scroll_view_up(&mut self, lines: usize) {
+        self.view_scroll = self.view_scroll.saturating_sub(lines);
+    }
+
 
     pub fn render(&mut self, frame: &mut Frame) {
         let size = frame.area();
@@ -457,9 +826,50 @@ impl App {
                     .style(Style::default().fg(Color::Red));
                 frame.render_widget(error_msg, area);
             }
-            _ => {
-                self.render_router_list(frame, area);
-            }
+            _ => match self.view.clone() {
+                View::Routers => self.render_router_list(frame, area),
+                View::ServiceDetail(name) => {
+                    let lines = self.service_detail_lines(&name);
+                    self.render_scrollable(frame, area, lines);
+                }
+                View::ParseWarnings => {
+                    let lines = self.parse_warning_lines();
+                    self.render_scrollable(frame, area, lines);
+                }
+                View::ServersTransports => {
+                    let lines = self.servers_transport_lines();
+                    self.render_scrollable(frame, area, lines);
+                }
+                View::RouteTest => {
+                    let lines = self.route_test_lines();
+                    self.render_scrollable(frame, area, lines);
+                }
+                View::RouteConflicts => {
+                    let lines = self.route_conflict_lines();
+                    self.render_scrollable(frame, area, lines);
+                }
+                View::Migration => {
+                    let lines = self.migration_lines();
+                    self.render_scrollable(frame, area, lines);
+                }
+                View::Services => self.render_service_list(frame, area),
+                View::Lint => {
+                    let lines = self.lint_lines();
+                    self.render_scrollable(frame, area, lines);
+                }
+                View::Audit => {
+                    let lines = self.audit_lines();
+                    self.render_scrollable(frame, area, lines);
+                }
+                View::Redirects => {
+                    let lines = self.redirect_lines();
+                    self.render_scrollable(frame, area, lines);
+                }
+                View::Certificates => {
+                    let lines = self.certificate_lines();
+                    self.render_scrollable(frame, area, lines);
+                }
+            },
         }
     }
 
@@ -477,151 +887,25 @@ impl App {
         }
 
         let viewport_height = area.height as usize;
-        
-        // Ensure selected router is visible and adjust scroll if needed
-        self.ensure_selected_visible(viewport_height);
-        
-        // Generate all lines for all routers
-        let mut all_lines = Vec::new();
-        let all_services = self.traefik_data.as_ref().map(|d| &d.services[..]).unwrap_or(&[]);
-        
-        for (i, router) in self.filtered_routers.iter().enumerate() {
-            let selected = i == self.selected_router_index;
-            
-            // Get router status and active service using the proper TypeScript logic
-            let (router_status, _active_service, _alive_count) = get_router_status_info(router, all_services);
-            let is_down = router_status == ServiceStatus::Down;
-            
-            // Router name with appropriate emoji and colors based on status
-            let (icon, icon_color, name_style) = if is_down {
-                ("💀", Color::White, if selected { Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD) } else { Style::default().fg(Color::Red).add_modifier(Modifier::BOLD) })
-            } else {
-                ("⬢ ", Color::Cyan, if selected { Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD) } else { Style::default().fg(Color::White).add_modifier(Modifier::BOLD) })
-            };
-            
-            all_lines.push(Line::from(vec![
-                Span::styled(icon, Style::default().fg(icon_color)),
-                Span::styled(router.name.clone(), name_style),
-            ]));
-
-            // Rule with arrow
-            all_lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled("→", Style::default().fg(Color::Yellow)),
-                Span::raw(" "),
-                Span::styled(router.rule.clone(), Style::default().fg(Color::DarkGray)),
-            ]));
-
-            // Find the main service that matches the router
-            if let Some(main_service) = self.get_service_for_router(router) {
-                // Check if this is a failover service
-                if main_service.service_type.as_deref() == Some("failover") || main_service.failover.is_some() {
-                    // Show failover service
-                    all_lines.push(Line::from(vec![
-                        Span::raw("  └── "),
-                        Span::styled(
-                            format!("{} (failover)", main_service.name),
-                            Style::default().fg(Color::Magenta),
-                        ),
-                    ]));
-
-                    // Show failover target services
-                    let failover_services = get_failover_services(&main_service.name, all_services);
-                    
-                    if let Some(primary) = failover_services.primary {
-                        let primary_status = get_service_status(primary, all_services, &mut HashSet::new());
-                        let (status_icon, status_color, service_color, line_color) = match primary_status {
-                            ServiceStatus::Up => ("✓", Color::Green, Color::White, Color::White),
-                            ServiceStatus::Down => ("✗", Color::Red, Color::DarkGray, Color::DarkGray),
-                            ServiceStatus::Unknown => ("?", Color::Yellow, Color::DarkGray, Color::DarkGray),
-                        };
-                        
-                        all_lines.push(Line::from(vec![
-                            Span::styled("      ├── ", Style::default().fg(line_color)),
-                            Span::styled(status_icon, Style::default().fg(status_color)),
-                            Span::raw(" "),
-                            Span::styled(primary.name.clone(), Style::default().fg(service_color)),
-                        ]));
-                    }
-                    
-                    if let Some(fallback) = failover_services.fallback {
-                        let fallback_status = get_service_status(fallback, all_services, &mut HashSet::new());
-                        let (status_icon, status_color, service_color, line_color) = match fallback_status {
-                            ServiceStatus::Up => ("✓", Color::Green, Color::White, Color::White),
-                            ServiceStatus::Down => ("✗", Color::Red, Color::DarkGray, Color::DarkGray),
-                            ServiceStatus::Unknown => ("?", Color::Yellow, Color::DarkGray, Color::DarkGray),
-                        };
-                        
-                        all_lines.push(Line::from(vec![
-                            Span::styled("      └── ", Style::default().fg(line_color)),
-                            Span::styled(status_icon, Style::default().fg(status_color)),
-                            Span::raw(" "),
-                            Span::styled(fallback.name.clone(), Style::default().fg(service_color)),
-                        ]));
-                    }
-                } else {
-                    // Regular service
-                    all_lines.push(Line::from(vec![
-                        Span::raw("  └── "),
-                        Span::styled(main_service.name.clone(), Style::default().fg(Color::Magenta)),
-                    ]));
 
-                    // Show load balancer servers when selected
-                    if selected {
-                        if let Some(ref lb) = main_service.load_balancer {
-                            for (idx, server) in lb.servers.iter().enumerate() {
-                                let is_last = idx == lb.servers.len() - 1;
-                                let tree_char = if is_last { "└──" } else { "├──" };
-                                
-                                // Check server status from serverStatus if available
-                                let server_status = main_service.server_status
-                                    .as_ref()
-                                    .and_then(|status_map| status_map.get(&server.url))
-                                    .map(|s| s.as_str())
-                                    .unwrap_or("unknown");
-                                
-                                let (status_icon, status_color, server_color, line_color) = if server_status == "UP" {
-                                    ("✓", Color::Green, Color::White, Color::White)
-                                } else {
-                                    ("✗", Color::Red, Color::DarkGray, Color::DarkGray)
-                                };
-                                
-                                all_lines.push(Line::from(vec![
-                                    Span::styled(format!("      {} ", tree_char), Style::default().fg(line_color)),
-                                    Span::styled(status_icon, Style::default().fg(status_color)),
-                                    Span::raw(" "),
-                                    Span::styled(server.url.clone(), Style::default().fg(server_color)),
-                                ]));
-                            }
-                        }
-                    }
-                }
-            } else {
-                // Service not found
-                all_lines.push(Line::from(vec![
-                    Span::raw("  └── "),
-                    Span::styled(
-                        format!("{} (not found)", router.service),
-                        Style::default().fg(Color::Red),
-                    ),
-                ]));
-            }
+        // Generate the line block of every row
+        let blocks: Vec<Vec<Line<'static>>> = (0..self.rows.len())
+            .map(|i| self.row_lines(i))
+            .collect();
+        let block_heights: Vec<usize> = blocks.iter().map(|block| block.len()).collect();
 
-            // Add empty line after each router except the last one
-            if i < self.filtered_routers.len() - 1 {
-                all_lines.push(Line::from(""));
-            }
-        }
+        // Ensure selected row is visible and adjust scroll if needed
+        self.ensure_selected_visible(viewport_height, &block_heights);
 
         // Apply scrolling - show only the lines that fit in the viewport
-        let visible_lines: Vec<Line> = all_lines
+        let mut display_lines: Vec<Line> = blocks
             .into_iter()
+            .flatten()
             .skip(self.scroll_offset)
             .take(viewport_height)
             .collect();
 
         // Fill remaining space with empty lines if needed
-        let mut display_lines = visible_lines;
         while display_lines.len() < viewport_height {
             display_lines.push(Line::from(""));
         }
@@ -630,65 +914,1849 @@ impl App {
         frame.render_widget(paragraph, area);
     }
 
-    fn render_footer(&self, frame: &mut Frame, area: Rect) {
-        let footer = match &self.state {
-            AppState::Search => {
-                let search_content = if self.search_query.is_empty() {
-                    "Search: (type to filter routers) | ESC: exit | Enter: accept".to_string()
-                } else {
-                    format!("Search: {} | ESC: exit | Enter: accept", self.search_query)
-                };
-                Paragraph::new(search_content)
-                    .style(Style::default().fg(Color::Yellow))
-            }
-            AppState::Filtered => {
-                let mut footer_spans = vec![
-                    Span::raw("Filtered: "),
-                    Span::styled(&self.search_query, Style::default().fg(Color::Cyan)),
-                    Span::raw(" | q: quit | r: refresh | /: new search | ESC: clear filter | s: sort | sort: "),
-                ];
-
-                let sort_mode_str = match self.sort_mode {
-                    SortMode::Dead => "dead",
-                    SortMode::Name => "name",
-                };
-                footer_spans.push(Span::styled(sort_mode_str, Style::default().fg(Color::Cyan)));
+    /// Services with their servers, health check and routers, the selected one is highlighted
+    fn render_service_list(&mut self, frame: &mut Frame, area: Rect) {
+        let services = self.listed_services();
+        if services.is_empty() {
+            let empty_msg = if self.services_down_only {
+                "No services with a DOWN server"
+            } else {
+                "No services found"
+            };
+            frame.render_widget(Paragraph::new(empty_msg).style(Style::default().fg(Color::Gray)), area);
+            return;
+        }
 
-                if let Some(last_update) = self.last_update {
-                    let elapsed = last_update.elapsed();
-                    footer_spans.push(Span::raw(format!(
-                        " | {}s ago",
-                        elapsed.as_secs()
-                    )));
-                }
+        // A refresh may have removed services
+        let selected = self.selected_service.min(services.len() - 1);
 
-                Paragraph::new(Line::from(footer_spans))
-                    .style(Style::default().fg(Color::Gray))
-            }
-            _ => {
-                let sort_mode_str = match self.sort_mode {
-                    SortMode::Dead => "dead",
-                    SortMode::Name => "name",
-                };
+        let all_services = self.traefik_data.as_ref().map(|d| &d.services[..]).unwrap_or(&[]);
+        let blocks: Vec<Vec<Line<'static>>> = services
+            .iter()
+            .enumerate()
+            .map(|(index, service)| service_lines(service, all_services, &self.server_probes, index == selected))
+            .collect();
+        self.selected_service = selected;
+        let block_heights: Vec<usize> = blocks.iter().map(|block| block.len()).collect();
+        let viewport_height = area.height as usize;
+        self.view_scroll = scroll_to_block(self.view_scroll, self.selected_service, viewport_height, &block_heights);
 
-                let mut footer_spans = vec![
-                    Span::raw("q: quit | r: refresh | /: search | s: sort | sort: "),
-                    Span::styled(sort_mode_str, Style::default().fg(Color::Cyan)),
-                ];
+        let lines: Vec<Line> = blocks
+            .into_iter()
+            .flatten()
+            .skip(self.view_scroll)
+            .take(viewport_height)
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
 
-                if let Some(last_update) = self.last_update {
-                    let elapsed = last_update.elapsed();
-                    footer_spans.push(Span::raw(format!(
-                        " | {}s ago",
-                        elapsed.as_secs()
-                    )));
-                }
+    /// Render a read-only view, clamping the scroll offset to its content
+    fn render_scrollable(&mut self, frame: &mut Frame, area: Rect, lines: Vec<Line<'static>>) {
+        let viewport_height = area.height as usize;
+        let max_scroll = lines.len().saturating_sub(viewport_height);
+        if self.view_scroll > max_scroll {
+            self.view_scroll = max_scroll;
+        }
 
-                Paragraph::new(Line::from(footer_spans))
+        let visible_lines: Vec<Line> = lines
+            .into_iter()
+            .skip(self.view_scroll)
+            .take(viewport_height)
+            .collect();
+
+        frame.render_widget(Paragraph::new(visible_lines), area);
+    }
+
+    /// Entries that were skipped because they could not be parsed
+    fn parse_warning_lines(&self) -> Vec<Line<'static>> {
+        let warnings = self.traefik_data.as_ref().map(|d| &d.warnings[..]).unwrap_or(&[]);
+        if warnings.is_empty() {
+            return vec![Line::from(Span::styled(
+                "All routers and services were parsed successfully",
+                Style::default().fg(Color::Green),
+            ))];
+        }
+
+        let mut lines = vec![
+            section_title(&format!("{} entries skipped because they could not be parsed", warnings.len())),
+            Line::from(""),
+        ];
+        for warning in warnings {
+            lines.push(Line::from(vec![
+                Span::styled("⚠ ", Style::default().fg(Color::Yellow)),
+                Span::styled(format!("{} ", warning.kind), Style::default().fg(Color::DarkGray)),
+                Span::styled(warning.name.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+            ]));
+            lines.push(Line::from(Span::styled(
+                format!("    {}", warning.message),
+                Style::default().fg(Color::Gray),
+            )));
+        }
+        lines
+    }
+
+    /// Shadowed routers, ambiguous overlaps and duplicate hosts found by the route analysis
+    fn route_conflict_lines(&self) -> Vec<Line<'static>> {
+        if self.route_warnings.is_empty() {
+            return vec![Line::from(Span::styled(
+                "No shadowed or conflicting routes found",
+                Style::default().fg(Color::Green),
+            ))];
+        }
+
+        // Sections keyed by the warning badge
+        let sections = [
+            ("shadowed", "Shadowed routers"),
+            ("ambiguous", "Ambiguous overlaps with equal priority"),
+            ("duplicate host", "Hosts routed by several providers"),
+        ];
+
+        let mut lines = Vec::new();
+        for (badge, title) in sections {
+            let warnings: Vec<&RouteWarning> = self.route_warnings.iter().filter(|w| w.badge() == badge).collect();
+            if warnings.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(section_title(&format!("{} ({})", title, warnings.len())));
+            for warning in warnings {
+                lines.push(Line::from(vec![
+                    Span::styled("⚠ ", Style::default().fg(Color::Yellow)),
+                    Span::styled(warning.router.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                ]));
+                lines.push(Line::from(Span::styled(
+                    format!("    {}", warning.describe()),
+                    Style::default().fg(Color::Gray),
+                )));
+            }
+        }
+        lines
+    }
+
+    /// Lint findings, one section per kind
+    fn lint_lines(&self) -> Vec<Line<'static>> {
+        if self.lint_findings.is_empty() {
+            return vec![Line::from(Span::styled(
+                "No unused or dangling services, routers or middlewares found",
+                Style::default().fg(Color::Green),
+            ))];
+        }
+
+        let sections = [
+            ("missing service", "Routers with a missing service"),
+            ("missing middleware", "Routers with a missing middleware"),
+            ("broken failover", "Failover services with a missing primary or fallback"),
+            ("no servers", "Load balancers without servers"),
+            ("unused service", "Services not used by any router or service"),
+        ];
+
+        let mut lines = Vec::new();
+        for (badge, title) in sections {
+            let findings: Vec<&LintFinding> = self.lint_findings.iter().filter(|f| f.badge() == badge).collect();
+            if findings.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(section_title(&format!("{} ({})", title, findings.len())));
+            for finding in findings {
+                let severity = finding.severity();
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{} ", severity.icon()), Style::default().fg(severity_color(severity))),
+                    Span::styled(finding.subject.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                ]));
+                lines.push(Line::from(Span::styled(
+                    format!("    {}", finding.describe()),
+                    Style::default().fg(Color::Gray),
+                )));
+            }
+        }
+        lines
+    }
+
+    /// Security audit findings, one section per rule
+    fn audit_lines(&self) -> Vec<Line<'static>> {
+        if self.audit_findings.is_empty() {
+            return vec![Line::from(Span::styled(
+                "No risky routing found",
+                Style::default().fg(Color::Green),
+            ))];
+        }
+
+        let mut lines = Vec::new();
+        for rule in AuditRule::ALL {
+            let findings: Vec<&AuditFinding> = self.audit_findings.iter().filter(|f| f.rule == rule).collect();
+            if findings.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(section_title(&format!("{} ({})", rule.title(), findings.len())));
+            for finding in findings {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", finding.severity.icon()),
+                        Style::default().fg(severity_color(finding.severity)),
+                    ),
+                    Span::styled(finding.router.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                ]));
+                lines.push(Line::from(Span::styled(
+                    format!("    {}", finding.detail),
+                    Style::default().fg(Color::Gray),
+                )));
+            }
+        }
+        lines
+    }
+
+    /// Hosts with their HTTP and HTTPS routers, problems first
+    fn redirect_lines(&self) -> Vec<Line<'static>> {
+        if self.redirect_pairs.is_empty() {
+            return vec![Line::from(Span::styled(
+                "No routers with a Host rule found",
+                Style::default().fg(Color::Gray),
+            ))];
+        }
+
+        let sections = [
+            (PairStatus::PlainHttp, "Served over plain HTTP"),
+            (PairStatus::NoHttps, "Redirect without an HTTPS router"),
+            (PairStatus::NoRedirect, "HTTPS only, no redirect from HTTP"),
+            (PairStatus::Paired, "Redirected to HTTPS"),
+        ];
+
+        let mut lines = Vec::new();
+        for (status, title) in sections {
+            let pairs: Vec<&HostRedirect> = self.redirect_pairs.iter().filter(|p| p.status() == status).collect();
+            if pairs.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(section_title(&format!("{} ({})", title, pairs.len())));
+            let (icon, color) = match status {
+                PairStatus::PlainHttp | PairStatus::NoHttps => ("⚠", Color::Yellow),
+                PairStatus::NoRedirect => ("·", Color::Gray),
+                PairStatus::Paired => ("✓", Color::Green),
+            };
+            for pair in pairs {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{} ", icon), Style::default().fg(color)),
+                    Span::styled(pair.host.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                ]));
+                let sides = [("plain http", &pair.plain), ("redirect  ", &pair.redirects), ("https     ", &pair.secure)];
+                for (label, routers) in sides {
+                    if !routers.is_empty() {
+                        lines.push(Line::from(vec![
+                            Span::styled(format!("    {} ", label), Style::default().fg(Color::DarkGray)),
+                            Span::styled(routers.join(", "), Style::default().fg(Color::Gray)),
+                        ]));
+                    }
+                }
+            }
+        }
+        lines
+    }
+
+    /// Certificate situation of a TLS router, None when no acme.json is configured
+    fn cert_status(&self, router: &Router) -> Option<CertStatus> {
+        if self.acme_paths.is_empty() {
+            return None;
+        }
+        router_cert_status(router, &self.certificates, &self.acme_resolvers, self.cert_warn_days, now_timestamp())
+    }
+
+    /// The most pressing problem with the certificates served for a router's hosts
+    fn served_cert_problem(&self, router: &Router) -> Option<&ServedCert> {
+        let entry_points = self.traefik_data.as_ref().map(|d| &d.entry_points[..]).unwrap_or(&[]);
+        let now = now_timestamp();
+        self.served_certs
+            .iter()
+            .filter(|served| served.serves(router, entry_points))
+            .filter(|served| served.status(self.cert_warn_days, now) != ServedStatus::Valid)
+            .min_by_key(|served| served.status(self.cert_warn_days, now))
+    }
+
+    /// Badges of the stored and served certificates of a router
+    fn cert_badges(&self, router: &Router) -> Vec<String> {
+        let mut badges: Vec<String> = self.cert_status(router).and_then(|status| status.badge()).into_iter().collect();
+        if let Some(badge) = self.served_cert_problem(router).and_then(|served| served.badge(self.cert_warn_days, now_timestamp())) {
+            if !badges.contains(&badge) {
+                badges.push(badge);
+            }
+        }
+        badges
+    }
+
+    /// Stored certificates, then the certificates the inspected entrypoints serve
+    fn certificate_lines(&self) -> Vec<Line<'static>> {
+        if self.acme_paths.is_empty() && self.tls_addresses.is_empty() {
+            return vec![Line::from(Span::styled(
+                "No certificates to check, start with --acme-json <path> or --tls-inspect <entrypoint>=<host>:<port>",
+                Style::default().fg(Color::Gray),
+            ))];
+        }
+
+        let mut lines = self.stored_certificate_lines();
+        let served = self.served_certificate_lines();
+        if !lines.is_empty() && !served.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.extend(served);
+
+        if lines.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "No certificates stored and no TLS routers with a Host rule",
+                Style::default().fg(Color::Gray),
+            )));
+        }
+        lines
+    }
+
+    /// Certificates the inspected entrypoints serve per host, problems first
+    fn served_certificate_lines(&self) -> Vec<Line<'static>> {
+        let routers = self.traefik_data.as_ref().map(|d| &d.routers[..]).unwrap_or(&[]);
+        let entry_points = self.traefik_data.as_ref().map(|d| &d.entry_points[..]).unwrap_or(&[]);
+        let now = now_timestamp();
+        let expiring_title = format!("Served certificates expiring within {} days", self.cert_warn_days);
+        let sections = [
+            (ServedStatus::Failed, "TLS handshake failed"),
+            (ServedStatus::DefaultCert, "Traefik's default certificate served"),
+            (ServedStatus::Mismatch, "Certificate for another host served"),
+            (ServedStatus::Expired, "Served certificates expired"),
+            (ServedStatus::Expiring, expiring_title.as_str()),
+            (ServedStatus::Valid, "Served certificates valid"),
+        ];
+
+        let mut lines = Vec::new();
+        for (status, title) in sections {
+            let served: Vec<&ServedCert> =
+                self.served_certs.iter().filter(|s| s.status(self.cert_warn_days, now) == status).collect();
+            if served.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(section_title(&format!("{} ({})", title, served.len())));
+            let (icon, color) = match status {
+                ServedStatus::Failed | ServedStatus::DefaultCert | ServedStatus::Expired => ("✗", Color::Red),
+                ServedStatus::Mismatch | ServedStatus::Expiring => ("⚠", Color::Yellow),
+                ServedStatus::Valid => ("✓", Color::Green),
+            };
+            for cert in served {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{} ", icon), Style::default().fg(color)),
+                    Span::styled(cert.host.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                    Span::styled(format!(" {} {}", cert.entry_point, cert.address), Style::default().fg(Color::DarkGray)),
+                ]));
+                let detail = match cert.result {
+                    Ok(ref info) if status != ServedStatus::DefaultCert && status != ServedStatus::Mismatch => format!(
+                        "expires {} ({}) | issuer {} | {}",
+                        info.expires,
+                        format_days_left(info.days_left(now)),
+                        info.issuer,
+                        info.domains.join(", ")
+                    ),
+                    _ => cert.describe(now),
+                };
+                lines.push(Line::from(Span::styled(format!("    {}", detail), Style::default().fg(Color::Gray))));
+                let served_routers: Vec<&str> = routers
+                    .iter()
+                    .filter(|router| cert.serves(router, entry_points))
+                    .map(|router| router.name.as_str())
+                    .collect();
+                if !served_routers.is_empty() {
+                    lines.push(Line::from(vec![
+                        Span::styled("    routers ", Style::default().fg(Color::DarkGray)),
+                        Span::styled(served_routers.join(", "), Style::default().fg(Color::Cyan)),
+                    ]));
+                }
+            }
+        }
+        lines
+    }
+
+    /// Stored certificates by expiry with the routers they serve, then TLS routers without one
+    fn stored_certificate_lines(&self) -> Vec<Line<'static>> {
+        if self.acme_paths.is_empty() {
+            return Vec::new();
+        }
+
+        let routers = self.traefik_data.as_ref().map(|d| &d.routers[..]).unwrap_or(&[]);
+        let now = now_timestamp();
+        let expiring_title = format!("Expiring within {} days", self.cert_warn_days);
+        let sections: [(&str, &str, Color); 3] = [
+            ("Expired", "✗", Color::Red),
+            (&expiring_title, "⚠", Color::Yellow),
+            ("Valid", "✓", Color::Green),
+        ];
+
+        let mut lines = Vec::new();
+        for (index, (title, icon, color)) in sections.into_iter().enumerate() {
+            let certs: Vec<&StoredCert> = self
+                .certificates
+                .iter()
+                .filter(|stored| {
+                    let days_left = stored.cert.days_left(now);
+                    let section = if days_left < 0 { 0 } else if days_left <= self.cert_warn_days { 1 } else { 2 };
+                    section == index
+                })
+                .collect();
+            if certs.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(section_title(&format!("{} ({})", title, certs.len())));
+            for stored in certs {
+                let cert = &stored.cert;
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{} ", icon), Style::default().fg(color)),
+                    Span::styled(cert.domains.join(", "), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                    Span::styled(format!(" {}", stored.resolver), Style::default().fg(Color::DarkGray)),
+                ]));
+                lines.push(Line::from(Span::styled(
+                    format!("    expires {} ({}) | issuer {}", cert.expires, format_days_left(cert.days_left(now)), cert.issuer),
+                    Style::default().fg(Color::Gray),
+                )));
+                let served: Vec<&str> = routers
+                    .iter()
+                    .filter(|router| router.tls.is_some() && router_hosts(router).iter().any(|host| cert.covers(host)))
+                    .map(|router| router.name.as_str())
+                    .collect();
+                if !served.is_empty() {
+                    lines.push(Line::from(vec![
+                        Span::styled("    routers ", Style::default().fg(Color::DarkGray)),
+                        Span::styled(served.join(", "), Style::default().fg(Color::Cyan)),
+                    ]));
+                }
+            }
+        }
+
+        let missing: Vec<(&Router, Vec<String>)> = routers
+            .iter()
+            .filter_map(|router| match router_cert_status(router, &self.certificates, &self.acme_resolvers, self.cert_warn_days, now) {
+                Some(CertStatus::Missing { hosts }) => Some((router, hosts)),
+                _ => None,
+            })
+            .collect();
+        if !missing.is_empty() {
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(section_title(&format!("TLS routers without a stored certificate ({})", missing.len())));
+            for (router, hosts) in missing {
+                lines.push(Line::from(vec![
+                    Span::styled("⚠ ", Style::default().fg(Color::Yellow)),
+                    Span::styled(router.name.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                ]));
+                lines.push(Line::from(Span::styled(
+                    format!("    no certificate for {}", hosts.join(", ")),
+                    Style::default().fg(Color::Gray),
+                )));
+            }
+        }
+        lines
+    }
+
+    /// Routers on v2 syntax with the suggested v3 rule and what needs a manual review
+    fn migration_lines(&self) -> Vec<Line<'static>> {
+        let routers = self.traefik_data.as_ref().map(|d| &d.routers[..]).unwrap_or(&[]);
+        let migrations = migrations(routers);
+        if migrations.is_empty() {
+            return vec![Line::from(Span::styled(
+                "All routers use v3 rule syntax",
+                Style::default().fg(Color::Green),
+            ))];
+        }
+
+        let defaulted = migrations.iter().filter(|m| m.defaulted).count();
+        let mut lines = vec![
+            section_title(&format!(
+                "{} routers on v2 syntax ({} without an explicit ruleSyntax)",
+                migrations.len(),
+                defaulted
+            )),
+            Line::from(""),
+        ];
+
+        for migration in migrations {
+            lines.push(Line::from(vec![
+                Span::styled(migration.router.name.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    if migration.defaulted { " v2 (defaulted)" } else { " v2" },
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+            lines.push(Line::from(vec![
+                Span::styled("  v2 ", Style::default().fg(Color::DarkGray)),
+                Span::styled(migration.router.rule.clone(), Style::default().fg(Color::Gray)),
+            ]));
+            match migration.rule {
+                Some(ref rule) if *rule == migration.router.rule => lines.push(Line::from(vec![
+                    Span::styled("  v3 ", Style::default().fg(Color::DarkGray)),
+                    Span::styled("unchanged", Style::default().fg(Color::Green)),
+                ])),
+                Some(ref rule) => lines.push(Line::from(vec![
+                    Span::styled("  v3 ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(rule.clone(), Style::default().fg(Color::Green)),
+                ])),
+                None => {}
+            }
+            for issue in migration.issues {
+                lines.push(Line::from(Span::styled(
+                    format!("  ⚠ {}", issue),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            lines.push(Line::from(""));
+        }
+        lines
+    }
+
+    /// HTTP and TCP serversTransports with the services that use them
+    fn servers_transport_lines(&self) -> Vec<Line<'static>> {
+        let Some(ref data) = self.traefik_data else {
+            return Vec::new();
+        };
+
+        let mut lines = vec![section_title("HTTP servers transports")];
+        if data.servers_transports.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  none reported (serversTransports are listed by Traefik v3 only)",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        for transport in &data.servers_transports {
+            lines.push(Line::from(Span::styled(
+                transport.name.clone(),
+                Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            )));
+            push_transport_details(&mut lines, transport, "");
+
+            let mut users: Vec<String> = data
+                .services
+                .iter()
+                .filter(|service| service.load_balancer.is_some())
+                .filter(|service| find_servers_transport(service, &data.servers_transports).is_some_and(|t| t.name == transport.name))
+                .map(|service| service.name.clone())
+                .collect();
+            users.sort();
+            if !users.is_empty() {
+                lines.push(field_line("Used by", users.join(", ")));
+            }
+            lines.push(Line::from(""));
+        }
+
+        lines.push(section_title("TCP servers transports"));
+        if data.tcp_servers_transports.is_empty() {
+            lines.push(Line::from(Span::styled("  none reported", Style::default().fg(Color::DarkGray))));
+        }
+        for transport in &data.tcp_servers_transports {
+            lines.push(Line::from(Span::styled(
+                transport.name.clone(),
+                Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            )));
+            push_tcp_transport_details(&mut lines, transport);
+            lines.push(Line::from(""));
+        }
+
+        lines
+    }
+
+    /// Which router handles the request typed into the route tester
+    fn route_test_lines(&self) -> Vec<Line<'static>> {
+        let req = match self.route_request {
+            Some(Ok(ref req)) => req,
+            Some(Err(ref e)) => {
+                return vec![Line::from(Span::styled(e.clone(), Style::default().fg(Color::Red)))];
+            }
+            None => return Vec::new(),
+        };
+        let Some(ref data) = self.traefik_data else {
+            return Vec::new();
+        };
+
+        let result = test_route(&data.routers, req);
+        let mut lines = vec![
+            field_line(
+                "Request",
+                format!(
+                    "{} {}://{}{}",
+                    req.method,
+                    if req.tls { "https" } else { "http" },
+                    req.host,
+                    req.path
+                ),
+            ),
+            field_line("Entrypoint", req.entrypoint.clone().unwrap_or_else(|| "any".to_string())),
+            Line::from(""),
+        ];
+
+        let Some(winner) = result.matched.first() else {
+            lines.push(Line::from(Span::styled(
+                "✗ No router matches, Traefik would answer 404",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+            push_unparsable(&mut lines, &result.unparsable);
+            return lines;
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled("✓ ", Style::default().fg(Color::Green)),
+            Span::styled(winner.router.name.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+            Span::styled(format_priority(winner.priority, winner.priority_defaulted), Style::default().fg(Color::Cyan)),
+        ]));
+        lines.push(field_line("Rule", winner.router.rule.clone()));
+
+        let (path, servers) = route_destination(winner.router, &data.services);
+        if path.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("  Service: ", Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    format!("{} (no healthy service, Traefik would answer 503)", winner.router.service),
+                    Style::default().fg(Color::Red),
+                ),
+            ]));
+        } else {
+            lines.push(field_line("Service", path.join(" → ")));
+            if servers.is_empty() {
+                lines.push(field_line("Servers", "none".to_string()));
+            }
+            for server in servers {
+                lines.push(Line::from(vec![
+                    Span::raw("    "),
+                    Span::styled("✓ ", Style::default().fg(Color::Green)),
+                    Span::styled(server, Style::default().fg(Color::White)),
+                ]));
+            }
+        }
+
+        if result.matched.len() > 1 {
+            lines.push(Line::from(""));
+            lines.push(section_title("Also matched (lower priority)"));
+            for candidate in &result.matched[1..] {
+                lines.push(Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled(candidate.router.name.clone(), Style::default().fg(Color::Gray)),
+                    Span::styled(
+                        format_priority(candidate.priority, candidate.priority_defaulted),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]));
+            }
+        }
+
+        push_unparsable(&mut lines, &result.unparsable);
+        lines
+    }
+
+    /// Detail view of a service and every service it is composed of
+    fn service_detail_lines(&self, service_name: &str) -> Vec<Line<'static>> {
+        let all_services = self.traefik_data.as_ref().map(|d| &d.services[..]).unwrap_or(&[]);
+        let transports = self.traefik_data.as_ref().map(|d| &d.servers_transports[..]).unwrap_or(&[]);
+        let tree = build_service_tree(service_name, all_services);
+        let mut lines = Vec::new();
+
+        if tree.service.is_none() {
+            lines.push(Line::from(Span::styled(
+                format!("Service {} not found", service_name),
+                Style::default().fg(Color::Red),
+            )));
+            return lines;
+        }
+
+        // Composition tree with all servers expanded
+        if !tree.children.is_empty() {
+            lines.push(section_title("Composition"));
+            let mut root_spans = vec![
+                Span::raw("  "),
+                Span::styled(tree.name.clone(), Style::default().fg(Color::Magenta)),
+            ];
+            if let Some(kind) = kind_label(&tree.kind) {
+                root_spans.push(Span::styled(format!(" ({})", kind), Style::default().fg(Color::Magenta)));
+            }
+            lines.push(Line::from(root_spans));
+            push_node_children(&mut lines, &tree, &self.server_probes, "  ", true);
+            lines.push(Line::from(""));
+        }
+
+        // One section per distinct service in the tree
+        let mut seen = Vec::new();
+        let mut pending = vec![&tree];
+        while let Some(node) = pending.pop() {
+            pending.extend(node.children.iter().rev());
+            let Some(service) = node.service else { continue };
+            if node.kind == NodeKind::Cycle || seen.contains(&service.name) {
+                continue;
+            }
+            seen.push(service.name.clone());
+            push_service_details(&mut lines, node, service, transports, &self.server_probes);
+        }
+
+        lines
+    }
+
+    /// Lines of one list row, routers inside a group are indented and followed by a separator
+    fn row_lines(&self, row_index: usize) -> Vec<Line<'static>> {
+        let selected = row_index == self.selected_row;
+        let mut lines = match self.rows[row_index] {
+            Row::Group(index) => return vec![self.group_header_line(&self.groups[index], selected)],
+            Row::Router(index) => self.router_lines(index, selected),
+        };
+
+        if self.list_group_mode() != GroupMode::None {
+            for line in lines.iter_mut() {
+                line.spans.insert(0, Span::raw("  "));
+            }
+        }
+
+        // Add empty line after each router except the last one
+        if row_index < self.rows.len() - 1 {
+            lines.push(Line::from(""));
+        }
+
+        lines
+    }
+
+    /// Group name with router count and rolled up status
+    fn group_header_line(&self, group: &RouterGroup, selected: bool) -> Line<'static> {
+        let (icon, color) = match group.status() {
+            ServiceStatus::Up => ("✓", Color::Green),
+            ServiceStatus::Down => ("✗", Color::Red),
+            ServiceStatus::Unknown if group.down > 0 => ("!", Color::Yellow),
+            ServiceStatus::Unknown => ("?", Color::Gray),
+        };
+        let name_style = if selected {
+            Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+        };
+
+        let collapsed = self.collapsed_groups.contains(&group.name);
+
+        let mut spans = vec![
+            Span::styled(if collapsed { "▸ " } else { "▾ " }, Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("{} ", icon), Style::default().fg(color)),
+            Span::styled(group.name.clone(), name_style),
+            Span::styled(
+                format!(" {} router{}", group.routers.len(), if group.routers.len() == 1 { "" } else { "s" }),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(format!(" ↑{}", group.up), Style::default().fg(Color::Green)),
+        ];
+        if group.down > 0 {
+            spans.push(Span::styled(format!(" ↓{}", group.down), Style::default().fg(Color::Red)));
+        }
+        Line::from(spans)
+    }
+
+    /// All lines of one router: name, rule and service tree
+    fn router_lines(&self, index: usize, selected: bool) -> Vec<Line<'static>> {
+        let router = &self.filtered_routers[index];
+        let all_services = self.traefik_data.as_ref().map(|d| &d.services[..]).unwrap_or(&[]);
+        let mut lines = Vec::new();
+
+        // Get router status and active service using the proper TypeScript logic
+        let (router_status, _active_service, _alive_count) = get_router_status_info(router, all_services);
+        let is_down = router_status == ServiceStatus::Down;
+
+        // Router name with appropriate emoji and colors based on status
+        let (icon, icon_color, name_style) = if is_down {
+            ("💀", Color::White, if selected { Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD) } else { Style::default().fg(Color::Red).add_modifier(Modifier::BOLD) })
+        } else {
+            ("⬢ ", Color::Cyan, if selected { Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD) } else { Style::default().fg(Color::White).add_modifier(Modifier::BOLD) })
+        };
+
+        let tree = self
+            .get_service_for_router(router)
+            .map(|main_service| build_service_tree(&main_service.name, all_services));
+
+        // Kubernetes routers show the resource they come from instead of the generated name
+        let mut name_spans = vec![Span::styled(icon, Style::default().fg(icon_color))];
+        match parse_kube_name(router, &self.namespace_hints) {
+            Some(kube) => {
+                name_spans.push(Span::styled(format!("{}/{}", kube.namespace, kube.name), name_style));
+                name_spans.push(Span::styled(format!(" {}", kube.kind), Style::default().fg(Color::DarkGray)));
+                if selected {
+                    name_spans.push(Span::styled(format!(" ({})", router.name), Style::default().fg(Color::DarkGray)));
+                }
+            }
+            None => name_spans.push(Span::styled(router.name.clone(), name_style)),
+        }
+        if self.group_mode == GroupMode::Compose {
+            if let Some((_, service)) = compose_service(router, &self.containers) {
+                name_spans.push(Span::styled(format!(" service {}", service), Style::default().fg(Color::DarkGray)));
+            }
+        }
+        let (priority, defaulted) = effective_priority(router);
+        name_spans.push(Span::styled(
+            format_priority(priority, defaulted),
+            Style::default().fg(if defaulted { Color::DarkGray } else { Color::Cyan }),
+        ));
+        if self.sort_mode == SortMode::Priority && self.list_group_mode() != GroupMode::EntryPoint {
+            name_spans.push(Span::styled(
+                format!(" on {}", entry_points_label(router)),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        if let Some(ref tls) = router.tls {
+            name_spans.push(Span::styled(format!(" {}", tls_label(tls)), Style::default().fg(Color::Green)));
+        }
+        if tree.as_ref().is_some_and(|tree| tree.has_cycle()) {
+            name_spans.push(Span::styled(" ↻ service cycle", Style::default().fg(Color::Red)));
+        }
+        let warnings: Vec<&RouteWarning> = self.route_warnings.iter().filter(|w| w.router == router.name).collect();
+        let mut badges: Vec<&str> = warnings.iter().map(|w| w.badge()).collect();
+        badges.sort();
+        badges.dedup();
+        for badge in self.redirect_badges(&router.name) {
+            if !badges.contains(&badge) {
+                badges.push(badge);
+            }
+        }
+        let cert_status = self.cert_status(router);
+        let cert_badges = self.cert_badges(router);
+        for badge in &cert_badges {
+            if !badges.contains(&badge.as_str()) {
+                badges.push(badge);
+            }
+        }
+        if !badges.is_empty() {
+            name_spans.push(Span::styled(format!(" ⚠ {}", badges.join(", ")), Style::default().fg(Color::Yellow)));
+        }
+        // What a request through the entrypoint got, regardless of Traefik's view of the servers
+        let probe = self.probe_results.iter().find(|probe| probe.router == router.name);
+        if let Some(probe) = probe {
+            let color = if probe.failed() { Color::Red } else { Color::DarkGray };
+            name_spans.push(Span::styled(format!(" ↯ {}", probe.summary()), Style::default().fg(color)));
+        }
+        lines.push(Line::from(name_spans));
+
+        // Container details when the Docker socket is configured
+        if let Some(container) = find_container(router, &self.containers) {
+            let state_color = if container.state == "running" { Color::Green } else { Color::Red };
+            let mut spans = vec![
+                Span::styled("  ◆ ", Style::default().fg(Color::Blue)),
+                Span::styled(container.name().to_string(), Style::default().fg(Color::Blue)),
+                Span::styled(format!(" {} ", container.image), Style::default().fg(Color::DarkGray)),
+                Span::styled(container.state.clone(), Style::default().fg(state_color)),
+            ];
+            if let Some(health) = container.health() {
+                let health_color = match health {
+                    "healthy" => Color::Green,
+                    "unhealthy" => Color::Red,
+                    _ => Color::Yellow,
+                };
+                spans.push(Span::styled(format!(" {}", health), Style::default().fg(health_color)));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        // Rule with arrow, the selected router can be pretty printed or scrolled sideways
+        let parsed = parse_router_rule(router);
+        if selected && self.rule_pretty && parsed.is_ok() {
+            let mut rule_lines = pretty_rule_lines(&router.rule).into_iter();
+            if let Some(first) = rule_lines.next() {
+                let mut spans = vec![
+                    Span::raw("  "),
+                    Span::styled("→", Style::default().fg(Color::Yellow)),
+                    Span::raw(" "),
+                ];
+                spans.extend(first);
+                lines.push(Line::from(spans));
+            }
+            for rule_line in rule_lines {
+                let mut spans = vec![Span::raw("    ")];
+                spans.extend(rule_line);
+                lines.push(Line::from(spans));
+            }
+        } else {
+            let scroll = if selected { self.rule_scroll } else { 0 };
+            let mut spans = vec![
+                Span::raw("  "),
+                Span::styled(if scroll > 0 { "‹" } else { "→" }, Style::default().fg(Color::Yellow)),
+                Span::raw(" "),
+            ];
+            spans.extend(skip_chars(rule_spans(&router.rule), scroll));
+            lines.push(Line::from(spans));
+
+            // Point at the position of a rule that doesn't parse
+            if let Err(ref e) = parsed {
+                if e.column > scroll {
+                    lines.push(Line::from(vec![
+                        Span::raw(" ".repeat(3 + e.column - scroll)),
+                        Span::styled(format!("^ {}", e.message), Style::default().fg(Color::Red)),
+                    ]));
+                } else {
+                    lines.push(Line::from(Span::styled(
+                        format!("    {}", e),
+                        Style::default().fg(Color::Red),
+                    )));
+                }
+            }
+        }
+
+        // Explain the route warnings of the selected router and link its redirect counterparts
+        if selected {
+            for warning in warnings {
+                lines.push(Line::from(Span::styled(
+                    format!("  ⚠ {}", warning.describe()),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            for pair in self.redirect_pairs.iter().filter(|pair| !pair.counterparts(&router.name).is_empty()) {
+                let label = if pair.secure.contains(&router.name) { "redirected from" } else { "redirects to" };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  ⇄ {} {} ", pair.host, label), Style::default().fg(Color::DarkGray)),
+                    Span::styled(pair.counterparts(&router.name).join(", "), Style::default().fg(Color::Cyan)),
+                ]));
+            }
+            if let Some(CertStatus::Missing { ref hosts }) = cert_status {
+                lines.push(Line::from(Span::styled(
+                    format!("  ⚠ no certificate stored for {}", hosts.join(", ")),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            if let Some(probe) = probe {
+                let color = if probe.failed() { Color::Red } else { Color::DarkGray };
+                lines.push(Line::from(Span::styled(format!("  ↯ {}", probe.describe()), Style::default().fg(color))));
+            }
+            if let Some(served) = self.served_cert_problem(router) {
+                lines.push(Line::from(Span::styled(
+                    format!("  ⚠ {} on {}: {}", served.host, served.entry_point, served.describe(now_timestamp())),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+        }
+
+        // Find the main service that matches the router
+        if let Some(tree) = tree {
+            let mut root_spans = vec![
+                Span::raw("  └── "),
+                Span::styled(tree.name.clone(), Style::default().fg(Color::Magenta)),
+            ];
+            if let Some(kind) = kind_label(&tree.kind) {
+                root_spans.push(Span::styled(format!(" ({})", kind), Style::default().fg(Color::Magenta)));
+            }
+            lines.push(Line::from(root_spans));
+
+            push_node_children(&mut lines, &tree, &self.server_probes, "      ", selected);
+        } else {
+            // Service not found
+            lines.push(Line::from(vec![
+                Span::raw("  └── "),
+                Span::styled(
+                    format!("{} (not found)", router.service),
+                    Style::default().fg(Color::Red),
+                ),
+            ]));
+        }
+
+        lines
+    }
+
+    /// Redirect pairing problems the router is responsible for
+    fn redirect_badges(&self, router_name: &str) -> Vec<&'static str> {
+        let mut badges = Vec::new();
+        for pair in &self.redirect_pairs {
+            let status = pair.status();
+            let responsible = match status {
+                PairStatus::PlainHttp => &pair.plain,
+                PairStatus::NoHttps => &pair.redirects,
+                PairStatus::Paired | PairStatus::NoRedirect => continue,
+            };
+            if let Some(badge) = status.badge() {
+                if responsible.iter().any(|name| name == router_name) && !badges.contains(&badge) {
+                    badges.push(badge);
+                }
+            }
+        }
+        badges
+    }
+
+    /// Footer title of the read-only views, None for the router list
+    fn view_title_spans(&self) -> Option<Vec<Span<'static>>> {
+        match self.view {
+            View::Routers => None,
+            View::ServiceDetail(ref name) => Some(vec![
+                Span::raw("Service: "),
+                Span::styled(name.clone(), Style::default().fg(Color::Cyan)),
+            ]),
+            View::ParseWarnings => Some(vec![Span::raw("Parse warnings")]),
+            View::ServersTransports => Some(vec![Span::raw("Servers transports")]),
+            View::RouteConflicts => Some(vec![Span::raw("Route conflicts")]),
+            View::Migration => Some(vec![Span::raw("v2 → v3 rule migration | x: export YAML")]),
+            View::Lint => Some(vec![Span::raw("Lint")]),
+            View::Audit => Some(vec![Span::raw("Security audit")]),
+            View::Redirects => Some(vec![Span::raw("HTTP → HTTPS redirects")]),
+            View::Certificates => Some(vec![Span::raw("Certificates")]),
+            View::Services => Some(vec![
+                Span::raw("Services"),
+                Span::styled(
+                    if self.services_down_only { " with a DOWN server" } else { "" },
+                    Style::default().fg(Color::Red),
+                ),
+                Span::raw(" | enter: routers | d: DOWN only"),
+            ]),
+            View::RouteTest => Some(vec![
+                Span::raw("Route test: "),
+                Span::styled(self.route_input.clone(), Style::default().fg(Color::Cyan)),
+                Span::raw(" | t: new test"),
+            ]),
+        }
+    }
+
+    /// Time since the last update and parse warning count, shared by all footers
+    fn status_spans(&self) -> Vec<Span<'static>> {
+        let mut spans = Vec::new();
+
+        if let Some(last_update) = self.last_update {
+            let elapsed = last_update.elapsed();
+            spans.push(Span::raw(format!(
+                " | {}s ago",
+                elapsed.as_secs()
+            )));
+        }
+
+        let warning_count = self.traefik_data.as_ref().map_or(0, |d| d.warnings.len());
+        if warning_count > 0 {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled(
+                format!("⚠ {} parse warning{} (w)", warning_count, if warning_count == 1 { "" } else { "s" }),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+
+        if self.hide_internal {
+            spans.push(Span::raw(" | @internal hidden (i)"));
+        }
+
+        match self.status_message {
+            Some(Ok(ref message)) => {
+                spans.push(Span::raw(" | "));
+                spans.push(Span::styled(message.clone(), Style::default().fg(Color::Green)));
+            }
+            Some(Err(ref message)) => {
+                spans.push(Span::raw(" | "));
+                spans.push(Span::styled(message.clone(), Style::default().fg(Color::Red)));
+            }
+            None => {}
+        }
+
+        // Unused services alone are not worth a footer note
+        let lint_count = self.lint_findings.iter().filter(|f| f.severity() != Severity::Info).count();
+        if lint_count > 0 {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled(
+                format!("✗ {} lint issue{} (L)", lint_count, if lint_count == 1 { "" } else { "s" }),
+                Style::default().fg(Color::Red),
+            ));
+        }
+
+        let audit_errors = self.audit_findings.iter().filter(|f| f.severity == Severity::Error).count();
+        if audit_errors > 0 {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled(
+                format!("✗ {} audit error{} (a)", audit_errors, if audit_errors == 1 { "" } else { "s" }),
+                Style::default().fg(Color::Red),
+            ));
+        }
+
+        let cert_count = self.traefik_data.as_ref().map_or(0, |data| {
+            data.routers
+                .iter()
+                .filter(|router| !self.cert_badges(router).is_empty())
+                .count()
+        });
+        if cert_count > 0 {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled(
+                format!("⚠ {} certificate issue{} (C)", cert_count, if cert_count == 1 { "" } else { "s" }),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+
+        let failing_servers = self.server_probes.iter().filter(|probe| probe.failed()).count();
+        if failing_servers > 0 {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled(
+                format!("✗ {} server{} failing probes", failing_servers, if failing_servers == 1 { "" } else { "s" }),
+                Style::default().fg(Color::Red),
+            ));
+        }
+
+        let failing_probes = self.probe_results.iter().filter(|probe| probe.failed()).count();
+        if failing_probes > 0 {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled(
+                format!("✗ {} failing probe{}", failing_probes, if failing_probes == 1 { "" } else { "s" }),
+                Style::default().fg(Color::Red),
+            ));
+        }
+
+        let conflict_count = self.route_warnings.len();
+        if conflict_count > 0 {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled(
+                format!("⚠ {} route conflict{} (c)", conflict_count, if conflict_count == 1 { "" } else { "s" }),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+
+        spans
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        // The route prompt can be opened from the route tester, what is typed takes the footer
+        let view_spans = if self.state == AppState::RouteInput { None } else { self.view_title_spans() };
+        if let Some(mut footer_spans) = view_spans {
+            footer_spans.push(Span::raw(" | ESC: back | j/k: scroll | r: refresh | q: quit"));
+            footer_spans.extend(self.status_spans());
+            let footer = Paragraph::new(Line::from(footer_spans))
+                .style(Style::default().fg(Color::Gray));
+            frame.render_widget(footer, area);
+            return;
+        }
+
+        let footer = match &self.state {
+            AppState::Search => {
+                let search_content = if self.search_query.is_empty() {
+                    "Search: (type to filter routers, ns:<namespace> for a Kubernetes namespace, service:<name> for a service, resolver:<name> for a cert resolver) | ESC: exit | Enter: accept".to_string()
+                } else {
+                    format!("Search: {} | ESC: exit | Enter: accept", self.search_query)
+                };
+                Paragraph::new(search_content)
+                    .style(Style::default().fg(Color::Yellow))
+            }
+            AppState::RouteInput => {
+                Paragraph::new(format!(
+                    "Route test: {}▏ | [METHOD] URL [Name:value]... [@entrypoint] | ESC: cancel | Enter: test",
+                    self.route_input
+                ))
+                .style(Style::default().fg(Color::Yellow))
+            }
+            AppState::Filtered => {
+                let mut footer_spans = vec![
+                    Span::raw("Filtered: "),
+                    Span::styled(&self.search_query, Style::default().fg(Color::Cyan)),
+                    Span::raw(if self.return_to_services {
+                        " | q: quit | r: refresh | /: new search | ESC: back to services | s: sort | sort: "
+                    } else {
+                        " | q: quit | r: refresh | /: new search | ESC: clear filter | s: sort | sort: "
+                    }),
+                ];
+
+                let sort_mode_str = match self.sort_mode {
+                    SortMode::Dead => "dead",
+                    SortMode::Name => "name",
+                    SortMode::Priority => "priority",
+                };
+                footer_spans.push(Span::styled(sort_mode_str, Style::default().fg(Color::Cyan)));
+                footer_spans.push(Span::raw(" | tab: group: "));
+                footer_spans.push(Span::styled(self.list_group_mode().name(), Style::default().fg(Color::Cyan)));
+
+                footer_spans.extend(self.status_spans());
+
+                Paragraph::new(Line::from(footer_spans))
+                    .style(Style::default().fg(Color::Gray))
+            }
+            _ => {
+                let sort_mode_str = match self.sort_mode {
+                    SortMode::Dead => "dead",
+                    SortMode::Name => "name",
+                    SortMode::Priority => "priority",
+                };
+
+                let mut footer_spans = vec![
+                    Span::raw("q: quit | r: refresh | /: search | enter: details | s: sort | sort: "),
+                    Span::styled(sort_mode_str, Style::default().fg(Color::Cyan)),
+                    Span::raw(" | tab: group: "),
+                    Span::styled(self.list_group_mode().name(), Style::default().fg(Color::Cyan)),
+                ];
+
+                footer_spans.extend(self.status_spans());
+
+                Paragraph::new(Line::from(footer_spans))
                     .style(Style::default().fg(Color::Gray))
             }
         };
 
         frame.render_widget(footer, area);
     }
-}
\ No newline at end of file
+}
+
+/// Scroll offset that keeps the selected block visible, given the line count of every block
+fn scroll_to_block(scroll_offset: usize, selected: usize, viewport_height: usize, block_heights: &[usize]) -> usize {
+    if block_heights.is_empty() || selected >= block_heights.len() {
+        return scroll_offset;
+    }
+
+    let block_start_line: usize = block_heights[..selected].iter().sum();
+    let block_lines = block_heights[selected];
+    let total_lines: usize = block_heights.iter().sum();
+    let mut scroll_offset = scroll_offset;
+
+    // Ensure the selected block is visible
+    if block_start_line < scroll_offset {
+        // Block is above the viewport, scroll up to show it
+        scroll_offset = block_start_line;
+    } else if block_start_line + block_lines > scroll_offset + viewport_height {
+        // Block is below the viewport, scroll down to show it
+        if block_lines <= viewport_height {
+            scroll_offset = (block_start_line + block_lines).saturating_sub(viewport_height);
+        } else {
+            // Block is bigger than viewport, show the start
+            scroll_offset = block_start_line;
+        }
+    }
+
+    // Ensure scroll_offset doesn't exceed bounds
+    if total_lines > viewport_height {
+        scroll_offset.min(total_lines - viewport_height)
+    } else {
+        0
+    }
+}
+
+/// Run a future on the runtime, its result is picked up with `poll_task`
+fn spawn_task<T: Send + 'static>(task: impl Future<Output = T> + Send + 'static) -> oneshot::Receiver<T> {
+    let (sender, receiver) = oneshot::channel();
+    tokio::spawn(async move {
+        let _ = sender.send(task.await);
+    });
+    receiver
+}
+
+/// The result of a spawned task once it is done, a task that died is forgotten
+fn poll_task<T>(task: &mut Option<oneshot::Receiver<T>>) -> Option<T> {
+    let result = match task.as_mut()?.try_recv() {
+        Err(TryRecvError::Empty) => return None,
+        result => result.ok(),
+    };
+    *task = None;
+    result
+}
+
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Error => Color::Red,
+        Severity::Warning => Color::Yellow,
+        Severity::Info => Color::DarkGray,
+    }
+}
+
+fn kind_label(kind: &NodeKind) -> Option<&'static str> {
+    match kind {
+        NodeKind::Failover => Some("failover"),
+        NodeKind::Weighted => Some("weighted"),
+        NodeKind::Mirroring => Some("mirroring"),
+        NodeKind::Missing => Some("not found"),
+        NodeKind::Cycle => Some("cycle"),
+        NodeKind::LoadBalancer => None,
+    }
+}
+
+/// Status and latency of a server's last direct probe, in red when it failed
+fn server_probe_span(probes: &[ServerProbe], service: &str, server: &str) -> Option<Span<'static>> {
+    let probe = probes.iter().find(|probe| probe.service == service && probe.server == server)?;
+    let color = if probe.failed() { Color::Red } else { Color::DarkGray };
+    Some(Span::styled(format!(" ↯ {}", probe.summary()), Style::default().fg(color)))
+}
+
+/// Render the children of a service node as a tree, recursing into nested compositions.
+/// Servers of load balancers are only listed for the selected router.
+fn push_node_children(lines: &mut Vec<Line<'static>>, node: &ServiceNode, probes: &[ServerProbe], prefix: &str, selected: bool) {
+    let servers = if selected && node.kind == NodeKind::LoadBalancer {
+        node.service
+            .and_then(|service| service.load_balancer.as_ref())
+            .map(|lb| lb.servers.as_slice())
+            .unwrap_or(&[])
+    } else {
+        &[]
+    };
+
+    for (idx, server) in servers.iter().enumerate() {
+        let is_last = idx == servers.len() - 1;
+        let tree_char = if is_last { "└──" } else { "├──" };
+
+        // Check server status from serverStatus if available
+        let server_status = node.service
+            .and_then(|service| service.server_status.as_ref())
+            .and_then(|status_map| status_map.get(&server.url))
+            .map(|s| s.as_str())
+            .unwrap_or("unknown");
+
+        let (status_icon, status_color, server_color, line_color) = if server_status == "UP" {
+            ("✓", Color::Green, Color::White, Color::White)
+        } else {
+            ("✗", Color::Red, Color::DarkGray, Color::DarkGray)
+        };
+
+        let mut spans = vec![
+            Span::styled(format!("{}{} ", prefix, tree_char), Style::default().fg(line_color)),
+            Span::styled(status_icon, Style::default().fg(status_color)),
+            Span::raw(" "),
+            Span::styled(server.url.clone(), Style::default().fg(server_color)),
+        ];
+        spans.extend(server_probe_span(probes, &node.name, &server.url));
+        lines.push(Line::from(spans));
+    }
+
+    for (idx, child) in node.children.iter().enumerate() {
+        let is_last = idx == node.children.len() - 1;
+        let tree_char = if is_last { "└──" } else { "├──" };
+
+        let (status_icon, status_color) = match (&child.kind, &child.status) {
+            (NodeKind::Cycle, _) => ("↻", Color::Red),
+            (_, ServiceStatus::Up) => ("✓", Color::Green),
+            (_, ServiceStatus::Down) => ("✗", Color::Red),
+            (_, ServiceStatus::Unknown) => ("?", Color::Yellow),
+        };
+
+        // The active path is highlighted end to end, mirrors and idle branches are dimmed
+        let (name_style, line_color) = if child.active {
+            (Style::default().fg(Color::White).add_modifier(Modifier::BOLD), Color::Green)
+        } else if child.status == ServiceStatus::Up && !matches!(child.role, NodeRole::Mirror(_)) {
+            (Style::default().fg(Color::White), Color::White)
+        } else {
+            (Style::default().fg(Color::DarkGray), Color::DarkGray)
+        };
+
+        let mut spans = vec![
+            Span::styled(format!("{}{} ", prefix, tree_char), Style::default().fg(line_color)),
+            Span::styled(status_icon, Style::default().fg(status_color)),
+            Span::raw(" "),
+        ];
+        if matches!(child.role, NodeRole::Mirror(_)) {
+            spans.push(Span::styled("mirror ", Style::default().fg(Color::DarkGray)));
+        }
+        spans.push(Span::styled(child.name.clone(), name_style));
+        match child.role {
+            NodeRole::Weight(Some(weight)) => {
+                spans.push(Span::styled(format!(" w={}", weight), Style::default().fg(Color::Cyan)));
+            }
+            NodeRole::Mirror(Some(percent)) => {
+                spans.push(Span::styled(format!(" {}%", percent), Style::default().fg(Color::Cyan)));
+            }
+            _ => {}
+        }
+        if let Some(kind) = kind_label(&child.kind) {
+            let kind_color = match child.kind {
+                NodeKind::Cycle | NodeKind::Missing => Color::Red,
+                _ => Color::Magenta,
+            };
+            spans.push(Span::styled(format!(" ({})", kind), Style::default().fg(kind_color)));
+        }
+        lines.push(Line::from(spans));
+
+        let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+        push_node_children(lines, child, probes, &child_prefix, selected);
+    }
+}
+
+fn section_title(title: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        title.to_string(),
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+    ))
+}
+
+/// A "  label: value" line, used by the detail views
+fn field_line(label: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("  {}: ", label), Style::default().fg(Color::DarkGray)),
+        Span::styled(value, Style::default().fg(Color::White)),
+    ])
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes".to_string() } else { "no".to_string() }
+}
+
+fn format_sticky(sticky: &Sticky) -> String {
+    let Some(ref cookie) = sticky.cookie else {
+        return "enabled".to_string();
+    };
+
+    let mut parts = vec![format!("cookie {}", cookie.name.as_deref().unwrap_or("(generated)"))];
+    if cookie.secure == Some(true) {
+        parts.push("secure".to_string());
+    }
+    if cookie.http_only == Some(true) {
+        parts.push("httpOnly".to_string());
+    }
+    if let Some(ref same_site) = cookie.same_site {
+        parts.push(format!("sameSite={}", same_site));
+    }
+    if let Some(max_age) = cookie.max_age {
+        parts.push(format!("maxAge={}", max_age));
+    }
+    if let Some(ref path) = cookie.path {
+        parts.push(format!("path={}", path));
+    }
+    parts.join(", ")
+}
+
+/// Detail section of a single service: general info, load balancer settings, servers and health check
+fn push_service_details(
+    lines: &mut Vec<Line<'static>>,
+    node: &ServiceNode,
+    service: &Service,
+    transports: &[ServersTransport],
+    probes: &[ServerProbe],
+) {
+    let (status_icon, status_color) = match node.status {
+        ServiceStatus::Up => ("✓", Color::Green),
+        ServiceStatus::Down => ("✗", Color::Red),
+        ServiceStatus::Unknown => ("?", Color::Yellow),
+    };
+
+    lines.push(Line::from(vec![
+        Span::styled(status_icon, Style::default().fg(status_color)),
+        Span::raw(" "),
+        Span::styled(service.name.clone(), Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
+    ]));
+    lines.push(field_line("Type", kind_label(&node.kind).unwrap_or("loadbalancer").to_string()));
+    lines.push(field_line("Provider", service.provider.clone()));
+    lines.push(field_line("Status", service.status.clone()));
+    if let Some(ref used_by) = service.used_by {
+        if !used_by.is_empty() {
+            lines.push(field_line("Used by", used_by.join(", ")));
+        }
+    }
+
+    if let Some(sticky) = service.weighted.as_ref().and_then(|w| w.sticky.as_ref()) {
+        lines.push(field_line("Sticky", format_sticky(sticky)));
+    }
+
+    if let Some(ref lb) = service.load_balancer {
+        lines.push(field_line(
+            "Pass host header",
+            lb.pass_host_header.map(yes_no).unwrap_or_else(|| "yes (default)".to_string()),
+        ));
+        match find_servers_transport(service, transports) {
+            Some(transport) => {
+                lines.push(field_line("Servers transport", transport.name.clone()));
+                push_transport_details(lines, transport, "  ");
+            }
+            // Traefik v2 has no serversTransports endpoint, only show explicitly configured names
+            None if lb.servers_transport.is_some() || !transports.is_empty() => {
+                lines.push(Line::from(vec![
+                    Span::styled("  Servers transport: ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(servers_transport_name(service), Style::default().fg(Color::White)),
+                    Span::styled(" (not found)", Style::default().fg(Color::Red)),
+                ]));
+            }
+            None => {}
+        }
+        if let Some(flush_interval) = lb.response_forwarding.as_ref().and_then(|r| r.flush_interval.clone()) {
+            lines.push(field_line("Flush interval", flush_interval));
+        }
+        if let Some(ref sticky) = lb.sticky {
+            lines.push(field_line("Sticky", format_sticky(sticky)));
+        }
+
+        lines.push(field_line("Servers", lb.servers.len().to_string()));
+        for server in &lb.servers {
+            let server_status = service
+                .server_status
+                .as_ref()
+                .and_then(|status_map| status_map.get(&server.url))
+                .map(|s| s.as_str())
+                .unwrap_or("unknown");
+            let (icon, color) = if server_status == "UP" {
+                ("✓", Color::Green)
+            } else {
+                ("✗", Color::Red)
+            };
+
+            let mut spans = vec![
+                Span::raw("    "),
+                Span::styled(icon, Style::default().fg(color)),
+                Span::raw(" "),
+                Span::styled(server.url.clone(), Style::default().fg(Color::White)),
+            ];
+            if let Some(weight) = server.weight {
+                spans.push(Span::styled(format!(" w={}", weight), Style::default().fg(Color::Cyan)));
+            }
+            spans.extend(server_probe_span(probes, &service.name, &server.url));
+            lines.push(Line::from(spans));
+        }
+
+        if let Some(ref hc) = lb.health_check {
+            lines.push(field_line("Health check", String::new()));
+            let options = [
+                ("scheme", hc.scheme.clone()),
+                ("mode", hc.mode.clone()),
+                ("method", hc.method.clone()),
+                ("hostname", hc.hostname.clone()),
+                ("port", hc.port.map(|p| p.to_string())),
+                ("path", hc.path.clone()),
+                ("status", hc.status.map(|s| s.to_string())),
+                ("interval", hc.interval.clone()),
+                ("timeout", hc.timeout.clone()),
+                ("followRedirects", hc.follow_redirects.map(yes_no)),
+            ];
+            for (label, value) in options {
+                if let Some(value) = value {
+                    lines.push(field_line(&format!("  {}", label), value));
+                }
+            }
+            if let Some(ref headers) = hc.headers {
+                let mut headers: Vec<_> = headers.iter().collect();
+                headers.sort();
+                for (name, value) in headers {
+                    lines.push(field_line(&format!("  header {}", name), value.clone()));
+                }
+            }
+        }
+    }
+
+    lines.push(Line::from(""));
+}
+
+/// One line summary of a health check, e.g. `GET http /health every 10s timeout 3s`
+fn format_health_check(hc: &HealthCheck) -> String {
+    let mut parts = vec![hc.method.clone().unwrap_or_else(|| "GET".to_string())];
+    if let Some(ref scheme) = hc.scheme {
+        parts.push(scheme.clone());
+    }
+    if let Some(ref hostname) = hc.hostname {
+        parts.push(format!("host {}", hostname));
+    }
+    if let Some(port) = hc.port {
+        parts.push(format!("port {}", port));
+    }
+    parts.push(hc.path.clone().unwrap_or_else(|| "/".to_string()));
+    if let Some(ref interval) = hc.interval {
+        parts.push(format!("every {}", interval));
+    }
+    if let Some(ref timeout) = hc.timeout {
+        parts.push(format!("timeout {}", timeout));
+    }
+    parts.join(" ")
+}
+
+/// Block of one service in the services view: status, type, provider, servers, health check and routers
+fn service_lines(service: &Service, all_services: &[Service], probes: &[ServerProbe], selected: bool) -> Vec<Line<'static>> {
+    let node = build_service_tree(&service.name, all_services);
+    let (status_icon, status_color) = match node.status {
+        ServiceStatus::Up => ("✓", Color::Green),
+        ServiceStatus::Down => ("✗", Color::Red),
+        ServiceStatus::Unknown => ("?", Color::Yellow),
+    };
+    let name_style = if selected {
+        Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+    };
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled(status_icon, Style::default().fg(status_color)),
+        Span::raw(" "),
+        Span::styled(service.name.clone(), name_style),
+        Span::styled(
+            format!(" {}", kind_label(&node.kind).unwrap_or("loadbalancer")),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])];
+    if !service.provider.is_empty() {
+        lines[0].spans.push(Span::styled(format!(" from {}", service.provider), Style::default().fg(Color::DarkGray)));
+    }
+
+    if let Some(ref lb) = service.load_balancer {
+        if lb.servers.is_empty() {
+            lines.push(Line::from(Span::styled("  no servers", Style::default().fg(Color::Red))));
+        }
+        for server in &lb.servers {
+            let server_status = service
+                .server_status
+                .as_ref()
+                .and_then(|status_map| status_map.get(&server.url))
+                .map(|s| s.as_str())
+                .unwrap_or("unknown");
+            let (icon, color) = if server_status == "UP" { ("✓", Color::Green) } else { ("✗", Color::Red) };
+            let mut spans = vec![
+                Span::styled(format!("  {} ", icon), Style::default().fg(color)),
+                Span::styled(server.url.clone(), Style::default().fg(Color::White)),
+                Span::styled(format!(" {}", server_status), Style::default().fg(color)),
+            ];
+            spans.extend(server_probe_span(probes, &service.name, &server.url));
+            lines.push(Line::from(spans));
+        }
+        if let Some(ref hc) = lb.health_check {
+            lines.push(field_line("Health check", format_health_check(hc)));
+        }
+    } else {
+        // Composite services list the services they delegate to
+        let children: Vec<String> = node.children.iter().map(|child| child.name.clone()).collect();
+        lines.push(field_line("Services", children.join(", ")));
+    }
+
+    match service.used_by.as_deref() {
+        Some(used_by) if !used_by.is_empty() => lines.push(field_line("Routers", used_by.join(", "))),
+        _ => lines.push(Line::from(Span::styled("  not used by any router", Style::default().fg(Color::DarkGray)))),
+    }
+
+    lines.push(Line::from(""));
+    lines
+}
+
+fn format_certificates(certificates: &[TransportCertificate]) -> String {
+    certificates
+        .iter()
+        .map(|cert| {
+            format!(
+                "{} / {}",
+                cert.cert_file.as_deref().unwrap_or("?"),
+                cert.key_file.as_deref().unwrap_or("?")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// TLS settings shared by HTTP and TCP transports, insecureSkipVerify is highlighted since it hides backend TLS problems.
+/// `label_indent` nests the labels below a heading, on top of the indent field_line adds
+fn push_transport_tls(
+    lines: &mut Vec<Line<'static>>,
+    label_indent: &str,
+    insecure_skip_verify: Option<bool>,
+    server_name: Option<&String>,
+    root_cas: Option<&Vec<String>>,
+    certificates: Option<&Vec<TransportCertificate>>,
+    peer_cert_uri: Option<&String>,
+) {
+    let insecure = insecure_skip_verify.unwrap_or(false);
+    lines.push(Line::from(vec![
+        Span::styled(format!("  {}insecureSkipVerify: ", label_indent), Style::default().fg(Color::DarkGray)),
+        Span::styled(yes_no(insecure), Style::default().fg(if insecure { Color::Red } else { Color::Green })),
+    ]));
+    let label = |name: &str| format!("{}{}", label_indent, name);
+    if let Some(server_name) = server_name {
+        lines.push(field_line(&label("serverName"), server_name.clone()));
+    }
+    if let Some(root_cas) = root_cas.filter(|cas| !cas.is_empty()) {
+        lines.push(field_line(&label("rootCAs"), root_cas.join(", ")));
+    }
+    if let Some(certificates) = certificates.filter(|certs| !certs.is_empty()) {
+        lines.push(field_line(&label("client certs"), format_certificates(certificates)));
+    }
+    if let Some(peer_cert_uri) = peer_cert_uri {
+        lines.push(field_line(&label("peerCertURI"), peer_cert_uri.clone()));
+    }
+}
+
+fn push_transport_details(lines: &mut Vec<Line<'static>>, transport: &ServersTransport, label_indent: &str) {
+    push_transport_tls(
+        lines,
+        label_indent,
+        transport.insecure_skip_verify,
+        transport.server_name.as_ref(),
+        transport.root_cas.as_ref(),
+        transport.certificates.as_ref(),
+        transport.peer_cert_uri.as_ref(),
+    );
+
+    let label = |name: &str| format!("{}{}", label_indent, name);
+    if let Some(max_idle) = transport.max_idle_conns_per_host {
+        lines.push(field_line(&label("maxIdleConnsPerHost"), max_idle.to_string()));
+    }
+    if transport.disable_http2 == Some(true) {
+        lines.push(field_line(&label("disableHTTP2"), "yes".to_string()));
+    }
+    if let Some(ref timeouts) = transport.forwarding_timeouts {
+        let values = [
+            ("dialTimeout", &timeouts.dial_timeout),
+            ("responseHeaderTimeout", &timeouts.response_header_timeout),
+            ("idleConnTimeout", &timeouts.idle_conn_timeout),
+            ("readIdleTimeout", &timeouts.read_idle_timeout),
+            ("pingTimeout", &timeouts.ping_timeout),
+        ];
+        for (name, value) in values {
+            if let Some(value) = value {
+                lines.push(field_line(&label(name), value.clone()));
+            }
+        }
+    }
+}
+
+fn push_tcp_transport_details(lines: &mut Vec<Line<'static>>, transport: &TcpServersTransport) {
+    let values = [
+        ("dialTimeout", &transport.dial_timeout),
+        ("dialKeepAlive", &transport.dial_keep_alive),
+        ("terminationDelay", &transport.termination_delay),
+    ];
+    for (name, value) in values {
+        if let Some(value) = value {
+            lines.push(field_line(name, value.clone()));
+        }
+    }
+
+    match transport.tls {
+        Some(ref tls) => push_transport_tls(
+            lines,
+            "",
+            tls.insecure_skip_verify,
+            tls.server_name.as_ref(),
+            tls.root_cas.as_ref(),
+            tls.certificates.as_ref(),
+            tls.peer_cert_uri.as_ref(),
+        ),
+        None => lines.push(field_line("tls", "no".to_string())),
+    }
+}
+
+/// TLS summary for router rows, e.g. "tls via le for example.com, *.example.com"
+fn tls_label(tls: &TlsConfig) -> String {
+    let mut label = "tls".to_string();
+    if tls.passthrough == Some(true) {
+        label.push_str(" passthrough");
+    }
+    if let Some(ref resolver) = tls.cert_resolver {
+        label.push_str(&format!(" via {}", resolver));
+    }
+    let domains = tls_domains(tls);
+    match domains.len() {
+        0 => {}
+        1 | 2 => label.push_str(&format!(" for {}", domains.join(", "))),
+        n => label.push_str(&format!(" for {}, {} +{}", domains[0], domains[1], n - 2)),
+    }
+    label
+}
+
+/// Time until a certificate expires, e.g. "in 12 days" or "3 days ago"
+fn format_days_left(days_left: i64) -> String {
+    match days_left {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        days if days > 0 => format!("in {} days", days),
+        days => format!("{} day{} ago", -days, if days == -1 { "" } else { "s" }),
+    }
+}
+
+fn format_priority(priority: i64, defaulted: bool) -> String {
+    if defaulted {
+        format!(" priority {} (rule length)", priority)
+    } else {
+        format!(" priority {}", priority)
+    }
+}
+
+fn token_style(kind: TokenKind, text: &str) -> Style {
+    match kind {
+        TokenKind::Ident if Matcher::from_name(text).is_some() => Style::default().fg(Color::Cyan),
+        TokenKind::String => Style::default().fg(Color::Green),
+        TokenKind::And | TokenKind::Or | TokenKind::Not => Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        TokenKind::LParen | TokenKind::RParen | TokenKind::Comma | TokenKind::Whitespace => {
+            Style::default().fg(Color::DarkGray)
+        }
+        // Unknown matchers and invalid input
+        TokenKind::Ident | TokenKind::Invalid => Style::default().fg(Color::Red),
+    }
+}
+
+/// A rule with matchers, string literals and operators highlighted
+fn rule_spans(rule: &str) -> Vec<Span<'static>> {
+    tokenize(rule)
+        .into_iter()
+        .map(|token| {
+            let text = &rule[token.start..token.end];
+            Span::styled(text.to_string(), token_style(token.kind, text))
+        })
+        .collect()
+}
+
+/// Drop the first `count` characters of a line
+fn skip_chars(spans: Vec<Span<'static>>, mut count: usize) -> Vec<Span<'static>> {
+    let mut result = Vec::new();
+    for span in spans {
+        let length = span.content.chars().count();
+        if count >= length {
+            count -= length;
+            continue;
+        }
+        let content: String = span.content.chars().skip(count).collect();
+        count = 0;
+        result.push(Span::styled(content, span.style));
+    }
+    result
+}
+
+/// A rule broken before every `&&` and `||`, with groups indented by their nesting
+fn pretty_rule_lines(rule: &str) -> Vec<Vec<Span<'static>>> {
+    let tokens = tokenize(rule);
+    let mut lines: Vec<Vec<Span<'static>>> = vec![Vec::new()];
+    // Whether each open parenthesis belongs to a matcher call rather than a group
+    let mut parens: Vec<bool> = Vec::new();
+    let mut previous = None;
+
+    let new_line = |lines: &mut Vec<Vec<Span<'static>>>, depth: usize| {
+        lines.push(vec![Span::raw("  ".repeat(depth))]);
+    };
+    let groups = |parens: &[bool]| parens.iter().filter(|call| !**call).count();
+
+    for token in tokens {
+        let text = &rule[token.start..token.end];
+        let style = token_style(token.kind, text);
+        match token.kind {
+            // Spacing is rebuilt around the breaks
+            TokenKind::Whitespace => continue,
+            TokenKind::And | TokenKind::Or => {
+                new_line(&mut lines, groups(&parens));
+                lines.last_mut().unwrap().push(Span::styled(format!("{} ", text), style));
+            }
+            TokenKind::LParen => {
+                let call = previous == Some(TokenKind::Ident);
+                lines.last_mut().unwrap().push(Span::styled(text.to_string(), style));
+                parens.push(call);
+                if !call {
+                    new_line(&mut lines, groups(&parens));
+                }
+            }
+            TokenKind::RParen => {
+                if parens.pop() == Some(false) {
+                    new_line(&mut lines, groups(&parens));
+                }
+                lines.last_mut().unwrap().push(Span::styled(text.to_string(), style));
+            }
+            TokenKind::Comma => lines.last_mut().unwrap().push(Span::styled(", ".to_string(), style)),
+            _ => lines.last_mut().unwrap().push(Span::styled(text.to_string(), style)),
+        }
+        previous = Some(token.kind);
+    }
+
+    lines
+}
+
+/// Sorted entrypoints of a router; routers without any listen on all of them
+fn entry_points_label(router: &Router) -> String {
+    if router.entry_points.is_empty() {
+        return "all entrypoints".to_string();
+    }
+    let mut entry_points = router.entry_points.clone();
+    entry_points.sort();
+    entry_points.join(",")
+}
+
+fn push_unparsable(lines: &mut Vec<Line<'static>>, routers: &[&Router]) {
+    if routers.is_empty() {
+        return;
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!("⚠ {} routers were not evaluated because their rule doesn't parse:", routers.len()),
+        Style::default().fg(Color::Yellow),
+    )));
+    for router in routers {
+        lines.push(Line::from(Span::styled(format!("  {}", router.name), Style::default().fg(Color::Gray))));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::grouping::router_provider;
+use crate::lint::{middleware_types, Severity};
+use crate::rule::{parse_router_rule, Expr, Matcher};
+use crate::redirects::{entry_point_redirects, entry_point_tls, router_entry_points};
+use crate::types::{EntryPoint, Router, TraefikData};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A check of the security audit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditRule {
+    /// Plain HTTP router on a public entrypoint that doesn't redirect to HTTPS
+    PlainHttp,
+    /// Router matching a sensitive path without an auth middleware
+    SensitivePathNoAuth,
+    /// `api@internal` or the dashboard reachable on a public entrypoint
+    InternalApiPublic,
+    /// TLS router relying on the default TLS options
+    TlsDefaultOptions,
+    /// Admin router without an IP allow list
+    AdminNoIpAllowList,
+}
+
+impl AuditRule {
+    pub const ALL: [AuditRule; 5] = [
+        AuditRule::InternalApiPublic,
+        AuditRule::SensitivePathNoAuth,
+        AuditRule::PlainHttp,
+        AuditRule::AdminNoIpAllowList,
+        AuditRule::TlsDefaultOptions,
+    ];
+
+    /// Name of the rule in the audit config
+    pub fn id(self) -> &'static str {
+        match self {
+            AuditRule::PlainHttp => "plain-http",
+            AuditRule::SensitivePathNoAuth => "sensitive-path-no-auth",
+            AuditRule::InternalApiPublic => "internal-api-public",
+            AuditRule::TlsDefaultOptions => "tls-default-options",
+            AuditRule::AdminNoIpAllowList => "admin-no-ip-allowlist",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            AuditRule::PlainHttp => "Plain HTTP without a redirect to HTTPS",
+            AuditRule::SensitivePathNoAuth => "Sensitive paths without authentication",
+            AuditRule::InternalApiPublic => "Traefik API or dashboard on a public entrypoint",
+            AuditRule::TlsDefaultOptions => "TLS with the default options",
+            AuditRule::AdminNoIpAllowList => "Admin routes without an IP allow list",
+        }
+    }
+
+    fn default_severity(self) -> Severity {
+        match self {
+            AuditRule::InternalApiPublic | AuditRule::SensitivePathNoAuth => Severity::Error,
+            AuditRule::PlainHttp | AuditRule::AdminNoIpAllowList => Severity::Warning,
+            AuditRule::TlsDefaultOptions => Severity::Info,
+        }
+    }
+}
+
+/// Severity of a rule in the audit config, `off` disables it
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+    Info,
+    Off,
+}
+
+/// What the audit considers sensitive, public and safe; every field is optional in the JSON file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AuditConfig {
+    /// Path prefixes that must sit behind an auth middleware
+    pub sensitive_paths: Vec<String>,
+    /// Path prefixes of admin routes, which should also be restricted by IP
+    pub admin_paths: Vec<String>,
+    /// Entrypoints reachable from outside, None means every entrypoint except `traefik` and loopback addresses
+    pub public_entry_points: Option<Vec<String>>,
+    /// Middleware types that count as authentication
+    pub auth_middleware_types: Vec<String>,
+    /// Severity per rule id, rules not listed keep their default
+    pub rules: HashMap<String, Level>,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            sensitive_paths: vec!["/admin".to_string(), "/metrics".to_string()],
+            admin_paths: vec!["/admin".to_string()],
+            public_entry_points: None,
+            auth_middleware_types: vec!["basicauth".to_string(), "digestauth".to_string(), "forwardauth".to_string()],
+            rules: HashMap::new(),
+        }
+    }
+}
+
+impl AuditConfig {
+    pub fn load(path: &str) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read audit config {}", path))?;
+        let config: AuditConfig =
+            serde_json::from_str(&text).with_context(|| format!("Failed to parse audit config {}", path))?;
+        if let Some(unknown) = config.rules.keys().find(|id| !AuditRule::ALL.iter().any(|rule| rule.id() == *id)) {
+            anyhow::bail!("Unknown audit rule {} in {}", unknown, path);
+        }
+        Ok(config)
+    }
+
+    /// Severity of a rule, None when it is turned off
+    fn severity(&self, rule: AuditRule) -> Option<Severity> {
+        match self.rules.get(rule.id()) {
+            None => Some(rule.default_severity()),
+            Some(Level::Error) => Some(Severity::Error),
+            Some(Level::Warning) => Some(Severity::Warning),
+            Some(Level::Info) => Some(Severity::Info),
+            Some(Level::Off) => None,
+        }
+    }
+
+    fn is_public(&self, entry_point: &str, entry_points: &[EntryPoint]) -> bool {
+        match self.public_entry_points {
+            Some(ref public) => public.iter().any(|name| name == entry_point),
+            None => {
+                let address = entry_points
+                    .iter()
+                    .find(|ep| ep.name == entry_point)
+                    .map(|ep| ep.address.as_str())
+                    .unwrap_or_default();
+                let loopback = ["127.0.0.1:", "localhost:", "[::1]:"].iter().any(|prefix| address.starts_with(prefix));
+                entry_point != "traefik" && !loopback
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditFinding {
+    pub router: String,
+    pub rule: AuditRule,
+    pub severity: Severity,
+    pub detail: String,
+}
+
+fn collect_paths(expr: &Expr, paths: &mut Vec<String>) {
+    match expr {
+        Expr::Matcher(call) => {
+            if matches!(call.matcher, Matcher::Path | Matcher::PathPrefix | Matcher::PathRegexp) {
+                paths.extend(call.args.iter().cloned());
+            }
+        }
+        // A negated path is one the router doesn't serve
+        Expr::Not(_) => {}
+        Expr::And(left, right) | Expr::Or(left, right) => {
+            collect_paths(left, paths);
+            collect_paths(right, paths);
+        }
+    }
+}
+
+/// The first of `prefixes` one of the router's path matchers falls under, ignoring the `^` anchor of regexps
+fn matched_prefix<'a>(paths: &[String], prefixes: &'a [String]) -> Option<&'a str> {
+    prefixes
+        .iter()
+        .find(|prefix| {
+            let prefix = prefix.to_ascii_lowercase();
+            paths.iter().any(|path| {
+                let path = path.to_ascii_lowercase();
+                path.starts_with(&prefix) || path.trim_start_matches('^').starts_with(&prefix)
+            })
+        })
+        .map(|prefix| prefix.as_str())
+}
+
+fn is_internal_api(router: &Router) -> bool {
+    let service = router.service.split('@').next().unwrap_or(&router.service);
+    matches!(service, "api" | "dashboard") && (router.service.ends_with("@internal") || router_provider(router) == Some("internal"))
+}
+
+/// Check one router against the enabled rules
+fn audit_router(router: &Router, data: &TraefikData, config: &AuditConfig, findings: &mut Vec<AuditFinding>) {
+    let mut report = |rule: AuditRule, detail: String| {
+        if let Some(severity) = config.severity(rule) {
+            findings.push(AuditFinding { router: router.name.clone(), rule, severity, detail });
+        }
+    };
+
+    // Without the middleware list a router can't be told to lack one, the checks relying on it are skipped
+    let types = middleware_types(router, &data.middlewares);
+    let lacks_type = |wanted: &[&str]| data.middlewares_available && !types.iter().any(|kind| wanted.contains(&kind.as_str()));
+    let entry_points = router_entry_points(router, &data.entry_points);
+    let public: Vec<&str> = entry_points
+        .iter()
+        .copied()
+        .filter(|ep| config.is_public(ep, &data.entry_points))
+        .collect();
+
+    let mut paths = Vec::new();
+    if let Ok(expr) = parse_router_rule(router) {
+        collect_paths(&expr, &mut paths);
+    }
+
+    // Routers Traefik creates itself, like the entrypoint redirections, aren't the user's to fix
+    let internal_router = router_provider(router) == Some("internal");
+    if router.tls.is_none() && !internal_router {
+        let plain: Vec<&str> = public
+            .iter()
+            .copied()
+            .filter(|name| !entry_point_tls(name, &data.entry_points) && !entry_point_redirects(name, &data.entry_points))
+            .collect();
+        if !plain.is_empty() && lacks_type(&["redirectscheme"]) {
+            report(AuditRule::PlainHttp, format!("served over plain HTTP on {}", plain.join(", ")));
+        }
+    }
+
+    if let Some(ref tls) = router.tls {
+        if tls.options.is_empty() || tls.options.split('@').next() == Some("default") {
+            report(AuditRule::TlsDefaultOptions, "no TLS options set, Traefik's defaults apply".to_string());
+        }
+    }
+
+    // The remaining rules are about exposure, routers only reachable internally are fine
+    if public.is_empty() {
+        return;
+    }
+
+    let auth_types: Vec<&str> = config.auth_middleware_types.iter().map(|kind| kind.as_str()).collect();
+    if let Some(prefix) = matched_prefix(&paths, &config.sensitive_paths) {
+        if lacks_type(&auth_types) {
+            report(AuditRule::SensitivePathNoAuth, format!("matches {} without an auth middleware", prefix));
+        }
+    }
+
+    if is_internal_api(router) {
+        report(AuditRule::InternalApiPublic, format!("{} reachable on {}", router.service, public.join(", ")));
+    }
+
+    let admin = is_internal_api(router) || matched_prefix(&paths, &config.admin_paths).is_some();
+    if admin && lacks_type(&["ipallowlist", "ipwhitelist"]) {
+        report(AuditRule::AdminNoIpAllowList, format!("admin route open to every client IP on {}", public.join(", ")));
+    }
+}
+
+/// Findings of every enabled rule, most severe first
+pub fn audit(data: &TraefikData, config: &AuditConfig) -> Vec<AuditFinding> {
+    let mut findings = Vec::new();
+    for router in &data.routers {
+        audit_router(router, data, config, &mut findings);
+    }
+    findings.sort_by(|a, b| a.severity.cmp(&b.severity).then_with(|| a.router.cmp(&b.router)));
+    findings
+}
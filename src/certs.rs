@@ -0,0 +1,237 @@
+use crate::grouping::router_hosts;
+use crate::types::{ParseWarning, Router, TlsConfig};
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use std::time::{SystemTime, UNIX_EPOCH};
+use x509_parser::extensions::GeneralName;
+use x509_parser::parse_x509_certificate;
+use x509_parser::pem::parse_x509_pem;
+use x509_parser::x509::X509Name;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// What a certificate states about itself
+#[derive(Debug, Clone)]
+pub struct CertInfo {
+    pub subject: String,
+    /// DNS names of the certificate
+    pub domains: Vec<String>,
+    pub issuer: String,
+    /// Expiry as a unix timestamp
+    pub not_after: i64,
+    /// Expiry date, e.g. 2026-01-31
+    pub expires: String,
+}
+
+/// Organization and common name, e.g. "Let's Encrypt R11"
+fn name_label(name: &X509Name) -> String {
+    name.iter_organization()
+        .chain(name.iter_common_name())
+        .filter_map(|attribute| attribute.as_str().ok())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl CertInfo {
+    fn from_x509(cert: &x509_parser::certificate::X509Certificate) -> Self {
+        let mut domains: Vec<String> = Vec::new();
+        if let Ok(Some(san)) = cert.subject_alternative_name() {
+            for name in &san.value.general_names {
+                if let GeneralName::DNSName(dns) = name {
+                    if !domains.iter().any(|domain| domain.eq_ignore_ascii_case(dns)) {
+                        domains.push(dns.to_string());
+                    }
+                }
+            }
+        }
+
+        // Certificates without SANs are only valid for their common name
+        let common_name = cert.subject().iter_common_name().find_map(|cn| cn.as_str().ok()).unwrap_or_default();
+        if domains.is_empty() && !common_name.is_empty() {
+            domains.push(common_name.to_string());
+        }
+
+        let not_after = cert.validity().not_after;
+        CertInfo {
+            subject: common_name.to_string(),
+            domains,
+            issuer: name_label(cert.issuer()),
+            not_after: not_after.timestamp(),
+            expires: not_after.to_datetime().date().to_string(),
+        }
+    }
+
+    /// Parse a DER certificate, as presented in a TLS handshake
+    pub fn from_der(der: &[u8]) -> Result<Self> {
+        let (_, cert) = parse_x509_certificate(der).map_err(|e| anyhow!("certificate doesn't parse: {}", e))?;
+        Ok(Self::from_x509(&cert))
+    }
+
+    /// Whole days until expiry, negative once expired
+    pub fn days_left(&self, now: i64) -> i64 {
+        (self.not_after - now).div_euclid(SECONDS_PER_DAY)
+    }
+
+    /// Whether the certificate is valid for a host, wildcards cover a single label
+    pub fn covers(&self, host: &str) -> bool {
+        self.domains.iter().any(|domain| {
+            let domain = domain.to_ascii_lowercase();
+            match domain.strip_prefix("*.") {
+                Some(parent) => host
+                    .split_once('.')
+                    .is_some_and(|(label, rest)| !label.is_empty() && rest == parent),
+                None => domain == host,
+            }
+        })
+    }
+}
+
+/// A certificate stored by an ACME certificate resolver
+#[derive(Debug, Clone)]
+pub struct StoredCert {
+    pub resolver: String,
+    /// Domains start with the main domain the resolver requested
+    pub cert: CertInfo,
+}
+
+/// Contents of an acme.json
+#[derive(Debug, Clone, Default)]
+pub struct AcmeStore {
+    /// Resolvers with an entry, including those without certificates yet
+    pub resolvers: Vec<String>,
+    pub certs: Vec<StoredCert>,
+}
+
+/// Certificate situation of a TLS router
+#[derive(Debug, Clone, PartialEq)]
+pub enum CertStatus {
+    /// Every host has a certificate, `days_left` is the soonest expiry
+    Valid { days_left: i64 },
+    /// A certificate expires within the warning window
+    Expiring { days_left: i64 },
+    Expired,
+    /// Hosts its resolver has no certificate for
+    Missing { hosts: Vec<String> },
+}
+
+impl CertStatus {
+    /// Short label for router rows, None when nothing needs attention
+    pub fn badge(&self) -> Option<String> {
+        match self {
+            CertStatus::Valid { .. } => None,
+            CertStatus::Expiring { days_left } => Some(format!("cert expires in {}d", days_left)),
+            CertStatus::Expired => Some("cert expired".to_string()),
+            CertStatus::Missing { .. } => Some("no cert".to_string()),
+        }
+    }
+}
+
+pub fn now_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Parse the leaf of a base64 encoded PEM chain, as acme.json stores it
+fn parse_certificate(resolver: &str, main: &str, encoded: &str) -> Result<StoredCert> {
+    let pem_chain = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .context("certificate is not valid base64")?;
+    let (_, pem) = parse_x509_pem(&pem_chain).map_err(|e| anyhow!("certificate is not valid PEM: {}", e))?;
+    let cert = pem.parse_x509().map_err(|e| anyhow!("certificate doesn't parse: {}", e))?;
+
+    let mut info = CertInfo::from_x509(&cert);
+    if !main.is_empty() {
+        info.domains.retain(|domain| !domain.eq_ignore_ascii_case(main));
+        info.domains.insert(0, main.to_string());
+    }
+
+    Ok(StoredCert { resolver: resolver.to_string(), cert: info })
+}
+
+/// Certificates of every resolver in an acme.json, entries that don't parse become warnings
+pub fn load_acme_json(path: &str) -> Result<(AcmeStore, Vec<ParseWarning>)> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    let resolvers: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path))?;
+
+    let mut store = AcmeStore { resolvers: resolvers.keys().cloned().collect(), certs: Vec::new() };
+    let mut warnings = Vec::new();
+    for (resolver, entry) in &resolvers {
+        let entries = entry.get("Certificates").and_then(|c| c.as_array()).map(|c| c.as_slice()).unwrap_or(&[]);
+        for (index, entry) in entries.iter().enumerate() {
+            let main = entry
+                .pointer("/domain/main")
+                .and_then(|main| main.as_str())
+                .unwrap_or_default();
+            let name = if main.is_empty() { format!("{}#{}", resolver, index) } else { format!("{}@{}", main, resolver) };
+            let encoded = entry.get("certificate").and_then(|c| c.as_str()).unwrap_or_default();
+
+            match parse_certificate(resolver, main, encoded) {
+                Ok(cert) => store.certs.push(cert),
+                Err(e) => warnings.push(ParseWarning { kind: "certificate".to_string(), name, message: format!("{:#}", e) }),
+            }
+        }
+    }
+
+    Ok((store, warnings))
+}
+
+/// Main domains and SANs a router's certificate resolver is configured to request
+pub fn tls_domains(tls: &TlsConfig) -> Vec<String> {
+    tls.domains
+        .iter()
+        .flat_map(|domain| std::iter::once(&domain.main).chain(&domain.sans))
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_ascii_lowercase())
+        .collect()
+}
+
+/// Certificates serving a TLS router's hosts and how soon they expire, None for routers without TLS or hosts.
+/// Certificates of the router's own resolver are preferred, the one expiring last wins. A host without a
+/// certificate only counts as missing when the router's resolver is one of `resolvers`, other certificates
+/// may come from files or a store traefiktop can't see.
+pub fn router_cert_status(
+    router: &Router,
+    certs: &[StoredCert],
+    resolvers: &[String],
+    warn_days: i64,
+    now: i64,
+) -> Option<CertStatus> {
+    let tls = router.tls.as_ref()?;
+    let mut hosts: Vec<String> = router_hosts(router).into_iter().filter(|host| !host.starts_with('~')).collect();
+    // Routers matching hosts by regexp name their certificate's domains explicitly
+    if hosts.is_empty() {
+        hosts = tls_domains(tls);
+    }
+    if hosts.is_empty() {
+        return None;
+    }
+
+    let resolver_loaded = tls.cert_resolver.as_ref().is_some_and(|resolver| resolvers.contains(resolver));
+    let mut missing = Vec::new();
+    let mut soonest: Option<i64> = None;
+    for host in hosts {
+        let covering = certs.iter().filter(|stored| stored.cert.covers(&host));
+        let resolver_certs: Vec<&StoredCert> = covering
+            .clone()
+            .filter(|stored| tls.cert_resolver.as_deref().is_none_or(|resolver| stored.resolver == resolver))
+            .collect();
+        let candidates = if resolver_certs.is_empty() { covering.collect() } else { resolver_certs };
+
+        match candidates.iter().map(|stored| stored.cert.days_left(now)).max() {
+            Some(days) => soonest = Some(soonest.map_or(days, |soonest| soonest.min(days))),
+            None if resolver_loaded => missing.push(host),
+            None => {}
+        }
+    }
+
+    Some(match soonest {
+        _ if !missing.is_empty() => CertStatus::Missing { hosts: missing },
+        Some(days_left) if days_left < 0 => CertStatus::Expired,
+        Some(days_left) if days_left <= warn_days => CertStatus::Expiring { days_left },
+        Some(days_left) => CertStatus::Valid { days_left },
+        None => return None,
+    })
+}
@@ -0,0 +1,189 @@
+use crate::grouping::router_provider;
+use crate::types::Router;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A container from the Docker Engine API `/containers/json`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Container {
+    #[serde(rename = "Id")]
+    pub id: String,
+    #[serde(rename = "Names", default)]
+    pub names: Vec<String>,
+    #[serde(rename = "Image", default)]
+    pub image: String,
+    /// running, exited, restarting...
+    #[serde(rename = "State", default)]
+    pub state: String,
+    /// Human readable status, carries the health check result, e.g. "Up 2 hours (healthy)"
+    #[serde(rename = "Status", default)]
+    pub status: String,
+    #[serde(rename = "Labels", default)]
+    pub labels: HashMap<String, String>,
+}
+
+impl Container {
+    pub fn name(&self) -> &str {
+        self.names
+            .first()
+            .map(|name| name.trim_start_matches('/'))
+            .unwrap_or(&self.id)
+    }
+
+    /// Health check result, None when the container has no health check
+    pub fn health(&self) -> Option<&'static str> {
+        if self.status.contains("(healthy)") {
+            Some("healthy")
+        } else if self.status.contains("(unhealthy)") {
+            Some("unhealthy")
+        } else if self.status.contains("(health: starting)") {
+            Some("starting")
+        } else {
+            None
+        }
+    }
+
+    fn compose(&self) -> Option<(&str, &str)> {
+        let project = self.labels.get("com.docker.compose.project")?;
+        let service = self.labels.get("com.docker.compose.service")?;
+        Some((project, service))
+    }
+}
+
+/// Talks to the Docker Engine API over its unix socket
+pub struct DockerClient {
+    socket: String,
+}
+
+impl DockerClient {
+    pub fn new(socket: String) -> Self {
+        Self { socket }
+    }
+
+    /// All containers, including stopped ones
+    pub async fn list_containers(&self) -> Result<Vec<Container>> {
+        let body = self.get("/containers/json?all=1").await?;
+        serde_json::from_slice(&body).context("Failed to parse Docker containers JSON")
+    }
+
+    #[cfg(unix)]
+    async fn get(&self, path: &str) -> Result<Vec<u8>> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // A daemon that hangs on accept or on reading the request is as stuck as one that never answers
+        let exchange = async {
+            let mut stream = tokio::net::UnixStream::connect(&self.socket)
+                .await
+                .with_context(|| format!("Failed to connect to Docker socket {}", self.socket))?;
+
+            // HTTP/1.0 makes the daemon close the connection after the response
+            let request = format!("GET {} HTTP/1.0\r\nHost: docker\r\n\r\n", path);
+            stream.write_all(request.as_bytes()).await.context("Failed to send request to Docker")?;
+
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response).await.context("Failed to read Docker response")?;
+            Ok::<_, anyhow::Error>(response)
+        };
+        let response = tokio::time::timeout(std::time::Duration::from_secs(5), exchange)
+            .await
+            .context("Docker API timed out")??;
+
+        parse_http_response(&response)
+    }
+
+    #[cfg(not(unix))]
+    async fn get(&self, _path: &str) -> Result<Vec<u8>> {
+        Err(anyhow!("Docker socket {} is only supported on unix", self.socket))
+    }
+}
+
+/// Status check and body of a raw HTTP/1.x response
+fn parse_http_response(response: &[u8]) -> Result<Vec<u8>> {
+    let split = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| anyhow!("Malformed Docker API response"))?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let body = &response[split + 4..];
+
+    let status = head.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        return Err(anyhow!(
+            "Docker API returned HTTP {}: {}",
+            status,
+            String::from_utf8_lossy(body).trim()
+        ));
+    }
+
+    let chunked = head
+        .lines()
+        .any(|line| line.to_ascii_lowercase().starts_with("transfer-encoding:") && line.contains("chunked"));
+    if chunked {
+        decode_chunked(body)
+    } else {
+        Ok(body.to_vec())
+    }
+}
+
+fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = body
+            .windows(2)
+            .position(|window| window == b"\r\n")
+            .ok_or_else(|| anyhow!("Malformed chunked Docker API response"))?;
+        let size_line = String::from_utf8_lossy(&body[..line_end]);
+        let size = usize::from_str_radix(size_line.split(';').next().unwrap_or_default().trim(), 16)
+            .context("Malformed chunk size in Docker API response")?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Ok(decoded);
+        }
+        if body.len() < size {
+            return Err(anyhow!("Truncated Docker API response"));
+        }
+        decoded.extend_from_slice(&body[..size]);
+        body = body.get(size + 2..).unwrap_or_default();
+    }
+}
+
+/// The container behind a docker provider router: by its `traefik.http.routers.<name>` labels,
+/// or by the `<service>-<project>` name Traefik generates for compose services
+pub fn find_container<'a>(router: &Router, containers: &'a [Container]) -> Option<&'a Container> {
+    if router_provider(router) != Some("docker") {
+        return None;
+    }
+    let name = router.name.split('@').next().unwrap_or(&router.name);
+    let label_prefix = format!("traefik.http.routers.{}.", name);
+
+    containers
+        .iter()
+        .find(|container| container.labels.keys().any(|key| key.starts_with(&label_prefix)))
+        .or_else(|| {
+            containers.iter().find(|container| {
+                container
+                    .compose()
+                    .is_some_and(|(project, service)| name == format!("{}-{}", service, project))
+            })
+        })
+}
+
+/// Compose project and service of a docker provider router, from its container labels or else its name
+pub fn compose_service(router: &Router, containers: &[Container]) -> Option<(String, String)> {
+    if router_provider(router) != Some("docker") {
+        return None;
+    }
+    if let Some((project, service)) = find_container(router, containers).and_then(|c| c.compose()) {
+        return Some((project.to_string(), service.to_string()));
+    }
+
+    // Without the container, read the name as `<service>-<project>` for a project other containers are in
+    let name = router.name.split('@').next().unwrap_or(&router.name);
+    containers.iter().filter_map(|container| container.compose()).find_map(|(project, _)| {
+        name.strip_suffix(project)
+            .and_then(|service| service.strip_suffix('-'))
+            .filter(|service| !service.is_empty())
+            .map(|service| (project.to_string(), service.to_string()))
+    })
+}
@@ -0,0 +1,198 @@
+use crate::docker::{compose_service, Container};
+use crate::kubernetes::parse_kube_name;
+use crate::rule::{parse_router_rule, Expr, Matcher};
+use crate::service_status::{get_router_status_info, ServiceStatus};
+use crate::types::{Router, Service};
+use std::collections::HashSet;
+
+/// How the router list is grouped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupMode {
+    None,
+    Host,
+    Provider,
+    EntryPoint,
+    Namespace,
+    Compose,
+}
+
+impl GroupMode {
+    pub fn next(self) -> GroupMode {
+        match self {
+            GroupMode::None => GroupMode::Host,
+            GroupMode::Host => GroupMode::Provider,
+            GroupMode::Provider => GroupMode::EntryPoint,
+            GroupMode::EntryPoint => GroupMode::Namespace,
+            GroupMode::Namespace => GroupMode::Compose,
+            GroupMode::Compose => GroupMode::None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GroupMode::None => "none",
+            GroupMode::Host => "host",
+            GroupMode::Provider => "provider",
+            GroupMode::EntryPoint => "entrypoint",
+            GroupMode::Namespace => "namespace",
+            GroupMode::Compose => "compose",
+        }
+    }
+}
+
+/// A line of the router list: a group header or a router
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Row {
+    /// Index into the groups
+    Group(usize),
+    /// Index into the filtered routers
+    Router(usize),
+}
+
+/// Routers sharing a group key, with their rolled up status
+#[derive(Debug, Clone)]
+pub struct RouterGroup {
+    pub name: String,
+    /// Indices into the filtered routers, in list order
+    pub routers: Vec<usize>,
+    pub up: usize,
+    pub down: usize,
+}
+
+impl RouterGroup {
+    /// Up when every router is up, Down when none is, Unknown for a mix or no status at all
+    pub fn status(&self) -> ServiceStatus {
+        if self.up > 0 && self.down == 0 {
+            ServiceStatus::Up
+        } else if self.down > 0 && self.up == 0 {
+            ServiceStatus::Down
+        } else {
+            ServiceStatus::Unknown
+        }
+    }
+}
+
+// Catch-all group names are parenthesized so they sort after the real ones
+const NO_HOST: &str = "(no host)";
+const NO_PROVIDER: &str = "(no provider)";
+const ALL_ENTRY_POINTS: &str = "(all entrypoints)";
+const NO_NAMESPACE: &str = "(not kubernetes)";
+const NO_COMPOSE_PROJECT: &str = "(not docker compose)";
+
+/// What grouping needs besides the routers
+pub struct GroupContext<'a> {
+    pub services: &'a [Service],
+    pub namespace_hints: &'a [String],
+    pub containers: &'a [Container],
+}
+
+/// Provider of a router, from the API field or the `@provider` name suffix
+pub fn router_provider(router: &Router) -> Option<&str> {
+    if !router.provider.is_empty() {
+        return Some(&router.provider);
+    }
+    router.name.rsplit_once('@').map(|(_, provider)| provider)
+}
+
+fn collect_hosts(expr: &Expr, hosts: &mut Vec<String>) {
+    match expr {
+        Expr::Matcher(call) => match call.matcher {
+            Matcher::Host | Matcher::HostHeader | Matcher::HostSNI => {
+                hosts.extend(call.args.iter().map(|host| host.to_ascii_lowercase()));
+            }
+            Matcher::HostRegexp | Matcher::HostSNIRegexp => {
+                hosts.extend(call.args.iter().map(|pattern| format!("~ {}", pattern)));
+            }
+            _ => {}
+        },
+        // A negated host doesn't say which host the router serves
+        Expr::Not(_) => {}
+        Expr::And(left, right) | Expr::Or(left, right) => {
+            collect_hosts(left, hosts);
+            collect_hosts(right, hosts);
+        }
+    }
+}
+
+/// Hosts a router serves, regexps prefixed with `~`. Empty when the rule has no host or doesn't parse.
+pub fn router_hosts(router: &Router) -> Vec<String> {
+    let mut hosts = Vec::new();
+    if let Ok(expr) = parse_router_rule(router) {
+        collect_hosts(&expr, &mut hosts);
+    }
+    hosts.sort();
+    hosts.dedup();
+    hosts
+}
+
+/// Groups a router belongs to, a router may appear in several
+fn group_keys(router: &Router, mode: GroupMode, context: &GroupContext) -> Vec<String> {
+    match mode {
+        GroupMode::None => Vec::new(),
+        GroupMode::Host => {
+            let hosts = router_hosts(router);
+            if hosts.is_empty() {
+                vec![NO_HOST.to_string()]
+            } else {
+                hosts
+            }
+        }
+        GroupMode::Provider => vec![router_provider(router).unwrap_or(NO_PROVIDER).to_string()],
+        // Routers without entrypoints listen on all default entrypoints
+        GroupMode::EntryPoint if router.entry_points.is_empty() => vec![ALL_ENTRY_POINTS.to_string()],
+        GroupMode::EntryPoint => router.entry_points.clone(),
+        GroupMode::Namespace => vec![parse_kube_name(router, context.namespace_hints)
+            .map(|kube| kube.namespace)
+            .unwrap_or_else(|| NO_NAMESPACE.to_string())],
+        GroupMode::Compose => vec![compose_service(router, context.containers)
+            .map(|(project, _)| project)
+            .unwrap_or_else(|| NO_COMPOSE_PROJECT.to_string())],
+    }
+}
+
+/// Group the filtered routers, keeping their order inside each group. Catch-all groups come last.
+pub fn group_routers(routers: &[Router], mode: GroupMode, context: &GroupContext) -> Vec<RouterGroup> {
+    let mut groups: Vec<RouterGroup> = Vec::new();
+
+    for (index, router) in routers.iter().enumerate() {
+        let status = get_router_status_info(router, context.services).0;
+        for key in group_keys(router, mode, context) {
+            let position = match groups.iter().position(|group| group.name == key) {
+                Some(position) => position,
+                None => {
+                    groups.push(RouterGroup { name: key, routers: Vec::new(), up: 0, down: 0 });
+                    groups.len() - 1
+                }
+            };
+            let group = &mut groups[position];
+            group.routers.push(index);
+            match status {
+                ServiceStatus::Up => group.up += 1,
+                ServiceStatus::Down => group.down += 1,
+                ServiceStatus::Unknown => {}
+            }
+        }
+    }
+
+    groups.sort_by(|a, b| {
+        let catch_all = |group: &RouterGroup| group.name.starts_with('(');
+        catch_all(a).cmp(&catch_all(b)).then_with(|| a.name.cmp(&b.name))
+    });
+    groups
+}
+
+/// The list rows for the groups, or one row per router when not grouping. Collapsed groups only show their header.
+pub fn build_rows(router_count: usize, groups: &[RouterGroup], mode: GroupMode, collapsed: &HashSet<String>) -> Vec<Row> {
+    if mode == GroupMode::None {
+        return (0..router_count).map(Row::Router).collect();
+    }
+
+    groups
+        .iter()
+        .enumerate()
+        .flat_map(|(index, group)| {
+            let routers: &[usize] = if collapsed.contains(&group.name) { &[] } else { &group.routers };
+            std::iter::once(Row::Group(index)).chain(routers.iter().map(|&router| Row::Router(router)))
+        })
+        .collect()
+}
@@ -0,0 +1,84 @@
+use crate::grouping::router_provider;
+use crate::types::Router;
+
+/// Namespaces that contain dashes and are present in most clusters
+const WELL_KNOWN_NAMESPACES: [&str; 3] = ["kube-system", "kube-public", "kube-node-lease"];
+
+/// The Kubernetes resource a router was generated from
+#[derive(Debug, Clone, PartialEq)]
+pub struct KubeName {
+    pub namespace: String,
+    pub name: String,
+    /// Resource kind, e.g. IngressRoute or HTTPRoute
+    pub kind: String,
+}
+
+/// Strip a trailing `-<hash>` of hex digits that Traefik appends to keep names unique
+fn strip_hash(name: &str, length: usize) -> &str {
+    match name.rsplit_once('-') {
+        Some((rest, hash)) if hash.len() == length && hash.chars().all(|c| c.is_ascii_hexdigit()) => rest,
+        _ => name,
+    }
+}
+
+/// Split `namespace-name`, preferring known namespaces since both parts may contain dashes
+fn split_namespace(name: &str, namespace_hints: &[String]) -> Option<(String, String)> {
+    let mut known: Vec<&str> = namespace_hints
+        .iter()
+        .map(|ns| ns.as_str())
+        .chain(WELL_KNOWN_NAMESPACES)
+        .collect();
+    // Longest first, so `kube-system` wins over a `kube` hint
+    known.sort_by_key(|ns| std::cmp::Reverse(ns.len()));
+
+    for namespace in known {
+        if let Some(rest) = name.strip_prefix(namespace).and_then(|rest| rest.strip_prefix('-')) {
+            if !rest.is_empty() {
+                return Some((namespace.to_string(), rest.to_string()));
+            }
+        }
+    }
+
+    name.split_once('-')
+        .filter(|(namespace, rest)| !namespace.is_empty() && !rest.is_empty())
+        .map(|(namespace, rest)| (namespace.to_string(), rest.to_string()))
+}
+
+/// Recover namespace, resource name and kind from the name Traefik's Kubernetes providers generate:
+/// - kubernetescrd: `<namespace>-<ingressroute>-<hash>`
+/// - kubernetes (Ingress): `<namespace>-<ingress>-<host>-<path>`, host and path can't be told apart from the name
+/// - kubernetesgateway: `<kind>-<namespace>-<route>-gw-<gateway namespace>-<gateway>-ep-<entrypoint>-<index>-<hash>`
+pub fn parse_kube_name(router: &Router, namespace_hints: &[String]) -> Option<KubeName> {
+    let provider = router_provider(router)?;
+    let name = router.name.split('@').next().unwrap_or(&router.name);
+
+    let (kind, resource) = match provider {
+        "kubernetescrd" => ("IngressRoute".to_string(), strip_hash(name, 20)),
+        "kubernetes" => ("Ingress".to_string(), name),
+        "kubernetesgateway" => {
+            let (kind, rest) = match name.split_once('-') {
+                Some((kind, rest)) if kind.ends_with("route") => (gateway_kind(kind), rest),
+                _ => ("HTTPRoute".to_string(), name),
+            };
+            let resource = match rest.split_once("-gw-") {
+                Some((resource, _)) => resource,
+                None => strip_hash(rest, 16),
+            };
+            (kind, resource)
+        }
+        _ => return None,
+    };
+
+    let (namespace, name) = split_namespace(resource, namespace_hints)?;
+    Some(KubeName { namespace, name, kind })
+}
+
+/// `httproute` → `HTTPRoute`
+fn gateway_kind(prefix: &str) -> String {
+    let protocol = prefix.trim_end_matches("route");
+    let protocol = match protocol {
+        "grpc" => "GRPC".to_string(),
+        other => other.to_ascii_uppercase(),
+    };
+    format!("{}Route", protocol)
+}
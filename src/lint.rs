@@ -0,0 +1,204 @@
+use crate::grouping::router_provider;
+use crate::service_status::find_service_by_name;
+use crate::types::{Middleware, Router, Service, TraefikData};
+
+/// Chains nested deeper than this are not followed
+const MAX_CHAIN_DEPTH: usize = 8;
+
+/// How much a finding matters, errors and warnings fail the headless check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn icon(self) -> &'static str {
+        match self {
+            Severity::Error => "✗",
+            Severity::Warning => "⚠",
+            Severity::Info => "·",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintKind {
+    /// No router uses the service and no other service delegates to it
+    UnusedService,
+    /// The router's service doesn't exist
+    MissingService { service: String },
+    /// A middleware of the router doesn't exist
+    MissingMiddleware { middleware: String },
+    /// The primary or fallback of a failover service doesn't exist
+    MissingFailoverPart { role: &'static str, service: String },
+    /// A load balancer without servers, Traefik answers 503
+    NoServers,
+}
+
+/// A problem with a router or service, `subject` is its name
+#[derive(Debug, Clone)]
+pub struct LintFinding {
+    pub subject: String,
+    pub kind: LintKind,
+}
+
+impl LintFinding {
+    /// Short label, also the section a finding is listed under
+    pub fn badge(&self) -> &'static str {
+        match self.kind {
+            LintKind::UnusedService => "unused service",
+            LintKind::MissingService { .. } => "missing service",
+            LintKind::MissingMiddleware { .. } => "missing middleware",
+            LintKind::MissingFailoverPart { .. } => "broken failover",
+            LintKind::NoServers => "no servers",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self.kind {
+            LintKind::MissingService { .. }
+            | LintKind::MissingMiddleware { .. }
+            | LintKind::MissingFailoverPart { .. } => Severity::Error,
+            LintKind::NoServers => Severity::Warning,
+            LintKind::UnusedService => Severity::Info,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.kind {
+            LintKind::UnusedService => "not used by any router or service".to_string(),
+            LintKind::MissingService { ref service } => format!("service {} doesn't exist", service),
+            LintKind::MissingMiddleware { ref middleware } => format!("middleware {} doesn't exist", middleware),
+            LintKind::MissingFailoverPart { role, ref service } => format!("{} service {} doesn't exist", role, service),
+            LintKind::NoServers => "load balancer has no servers, requests get a 503".to_string(),
+        }
+    }
+}
+
+/// Unqualified names refer to the provider of the router or service using them
+fn qualify(name: &str, provider: Option<&str>) -> String {
+    match provider {
+        Some(provider) if !name.contains('@') && !provider.is_empty() => format!("{}@{}", name, provider),
+        _ => name.to_string(),
+    }
+}
+
+/// Middleware referenced by a router or chain from the given provider
+pub fn find_middleware<'a>(name: &str, provider: Option<&str>, middlewares: &'a [Middleware]) -> Option<&'a Middleware> {
+    let qualified = qualify(name, provider);
+    middlewares
+        .iter()
+        .find(|middleware| middleware.name == qualified)
+        .or_else(|| middlewares.iter().find(|middleware| middleware.name == name))
+}
+
+/// Types of the router's middlewares, chains expanded
+pub fn middleware_types(router: &Router, middlewares: &[Middleware]) -> Vec<String> {
+    fn collect(names: &[String], provider: Option<&str>, middlewares: &[Middleware], depth: usize, types: &mut Vec<String>) {
+        for name in names {
+            let Some(middleware) = find_middleware(name, provider, middlewares) else {
+                continue;
+            };
+            if let Some(ref kind) = middleware.middleware_type {
+                types.push(kind.to_ascii_lowercase());
+            }
+            if let Some(ref chain) = middleware.chain {
+                if depth < MAX_CHAIN_DEPTH {
+                    let chain_provider = Some(middleware.provider.as_str()).filter(|p| !p.is_empty());
+                    collect(&chain.middlewares, chain_provider.or(provider), middlewares, depth + 1, types);
+                }
+            }
+        }
+    }
+
+    let mut types = Vec::new();
+    collect(router.middlewares.as_deref().unwrap_or(&[]), router_provider(router), middlewares, 0, &mut types);
+    types
+}
+
+/// Services another service delegates to
+fn child_services(service: &Service) -> Vec<&str> {
+    let mut children = Vec::new();
+    if let Some(ref failover) = service.failover {
+        children.extend([failover.service.as_str(), failover.fallback.as_str()]);
+    }
+    if let Some(ref weighted) = service.weighted {
+        children.extend(weighted.services.iter().flatten().map(|child| child.name.as_str()));
+    }
+    if let Some(ref mirroring) = service.mirroring {
+        children.push(mirroring.service.as_str());
+        children.extend(mirroring.mirrors.iter().flatten().map(|mirror| mirror.name.as_str()));
+    }
+    children
+}
+
+fn router_findings(router: &Router, data: &TraefikData, findings: &mut Vec<LintFinding>) {
+    if find_service_by_name(&router.service, &data.services).is_none() {
+        findings.push(LintFinding {
+            subject: router.name.clone(),
+            kind: LintKind::MissingService { service: router.service.clone() },
+        });
+    }
+
+    // Every reference would look dangling without the middleware list
+    if !data.middlewares_available {
+        return;
+    }
+    for middleware in router.middlewares.iter().flatten() {
+        if find_middleware(middleware, router_provider(router), &data.middlewares).is_none() {
+            findings.push(LintFinding {
+                subject: router.name.clone(),
+                kind: LintKind::MissingMiddleware { middleware: middleware.clone() },
+            });
+        }
+    }
+}
+
+fn service_findings(service: &Service, data: &TraefikData, used: &[&str], findings: &mut Vec<LintFinding>) {
+    if let Some(ref failover) = service.failover {
+        for (role, name) in [("primary", &failover.service), ("fallback", &failover.fallback)] {
+            if find_service_by_name(name, &data.services).is_none() {
+                findings.push(LintFinding {
+                    subject: service.name.clone(),
+                    kind: LintKind::MissingFailoverPart { role, service: name.clone() },
+                });
+            }
+        }
+    }
+
+    if service.load_balancer.as_ref().is_some_and(|lb| lb.servers.is_empty()) {
+        findings.push(LintFinding { subject: service.name.clone(), kind: LintKind::NoServers });
+    }
+
+    // Internal services are wired up by Traefik itself
+    let used_by_router = service.used_by.as_ref().is_some_and(|used_by| !used_by.is_empty());
+    if service.provider != "internal" && !used_by_router && !used.contains(&service.name.as_str()) {
+        findings.push(LintFinding { subject: service.name.clone(), kind: LintKind::UnusedService });
+    }
+}
+
+/// Unused services, dangling service and middleware references and load balancers without servers
+pub fn lint(data: &TraefikData) -> Vec<LintFinding> {
+    // Names of services some router or service resolves to
+    let used: Vec<&str> = data
+        .routers
+        .iter()
+        .map(|router| router.service.as_str())
+        .chain(data.services.iter().flat_map(child_services))
+        .filter_map(|name| find_service_by_name(name, &data.services))
+        .map(|service| service.name.as_str())
+        .collect();
+
+    let mut findings = Vec::new();
+    for router in &data.routers {
+        router_findings(router, data, &mut findings);
+    }
+    for service in &data.services {
+        service_findings(service, data, &used, &mut findings);
+    }
+
+    findings.sort_by(|a, b| a.severity().cmp(&b.severity()).then_with(|| a.subject.cmp(&b.subject)));
+    findings
+}
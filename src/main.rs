@@ -1,11 +1,24 @@
 mod api;
 mod app;
+mod audit;
+mod certs;
+mod docker;
+mod grouping;
+mod kubernetes;
+mod lint;
+mod migrate;
+mod probe;
+mod redirects;
+mod route_analysis;
+mod route_match;
+mod rule;
 mod service_status;
+mod tls_inspect;
 mod types;
 
-use app::{App, AppState};
+use app::{App, AppState, View};
 use anyhow::Context;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
     execute,
@@ -62,6 +75,7 @@ impl Drop for TerminalGuard {
     }
 }
 use std::{
+    net::IpAddr,
     time::{Duration, Instant},
 };
 use tracing::{error, info};
@@ -82,6 +96,42 @@ struct Cli {
     #[arg(long)]
     ignore: Vec<String>,
 
+    /// Kubernetes namespaces containing dashes, to split generated router names correctly. Can be used multiple times.
+    #[arg(long = "namespace", value_delimiter = ',')]
+    namespaces: Vec<String>,
+
+    /// Docker Engine API socket (e.g. /var/run/docker.sock) to show container, image, state and health of docker routers
+    #[arg(long)]
+    docker_socket: Option<String>,
+
+    /// JSON file adjusting the security audit: sensitive and admin paths, public entrypoints, auth middleware types and rule severities
+    #[arg(long)]
+    audit_config: Option<String>,
+
+    /// Traefik acme.json to check the stored certificates against the TLS routers. Can be used multiple times.
+    #[arg(long = "acme-json")]
+    acme_json: Vec<String>,
+
+    /// Entrypoint to handshake with for the hosts of its TLS routers, as <entrypoint>=<host>:<port>. Can be used multiple times.
+    #[arg(long = "tls-inspect", value_parser = tls_inspect::EntryPointAddress::parse)]
+    tls_inspect: Vec<tls_inspect::EntryPointAddress>,
+
+    /// Entrypoint to send a request through for each of its routers, as <entrypoint>=<host>:<port>. Can be used multiple times.
+    #[arg(long = "probe", value_parser = tls_inspect::EntryPointAddress::parse)]
+    probe: Vec<tls_inspect::EntryPointAddress>,
+
+    /// Request every load balancer server directly this often, in seconds, to show its latency and status next to Traefik's
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    server_probe_interval: Option<u64>,
+
+    /// File `x` in the migration view exports the suggested v3 rules to, never overwritten. Defaults to a timestamped name in the working directory.
+    #[arg(long)]
+    migration_export: Option<String>,
+
+    /// Flag certificates expiring within this many days
+    #[arg(long, default_value = "14")]
+    cert_warn_days: i64,
+
     /// Refresh interval in seconds
     #[arg(short, long, default_value = "30")]
     refresh: u64,
@@ -89,6 +139,138 @@ struct Cli {
     /// Just fetch and display data (don't start TUI)
     #[arg(long, alias = "oneshot")]
     headless: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show which router would handle a request, without starting the TUI
+    Route {
+        /// Request URL, e.g. https://shop.example.com/api/cart
+        url: String,
+
+        /// HTTP method
+        #[arg(short = 'X', long, default_value = "GET")]
+        method: String,
+
+        /// Request header as "Name: value". Can be used multiple times.
+        #[arg(short = 'H', long = "header")]
+        headers: Vec<String>,
+
+        /// Entrypoint the request arrives on (e.g. websecure)
+        #[arg(short, long)]
+        entrypoint: Option<String>,
+
+        /// Client IP used for ClientIP matchers
+        #[arg(long)]
+        client_ip: Option<IpAddr>,
+    },
+    /// Report unused services and dangling service/middleware references, exits with 1 on errors or warnings
+    Lint,
+    /// Report risky routing such as plain HTTP, unauthenticated sensitive paths and an exposed API, exits with 1 on errors
+    Audit,
+}
+
+/// Print which router handles a request, exits with 1 when no router matches
+async fn run_route_command(client: &api::TraefikClient, req: route_match::RouteRequest) -> anyhow::Result<()> {
+    let data = client.fetch_all_data().await?;
+    let result = route_match::test_route(&data.routers, &req);
+
+    println!(
+        "🔎 {} {}://{}{} (entrypoint: {})",
+        req.method,
+        if req.tls { "https" } else { "http" },
+        req.host,
+        req.path,
+        req.entrypoint.as_deref().unwrap_or("any")
+    );
+
+    for router in &result.unparsable {
+        println!("⚠️  Not evaluated, rule doesn't parse: {}", router.name);
+    }
+
+    let Some(winner) = result.matched.first() else {
+        println!("❌ No router matches, Traefik would answer 404");
+        std::process::exit(1);
+    };
+
+    let priority_note = if winner.priority_defaulted { " (rule length)" } else { "" };
+    println!("✅ {} - priority {}{}", winner.router.name, winner.priority, priority_note);
+    println!("   Rule: {}", winner.router.rule);
+
+    let (path, servers) = route_match::route_destination(winner.router, &data.services);
+    if path.is_empty() {
+        println!("   Service: {} (no healthy service, Traefik would answer 503)", winner.router.service);
+    } else {
+        println!("   Service: {}", path.join(" → "));
+        for server in servers {
+            println!("   Server: {}", server);
+        }
+    }
+
+    if result.matched.len() > 1 {
+        println!("\nAlso matched (lower priority):");
+        for candidate in &result.matched[1..] {
+            let note = if candidate.priority_defaulted { " (rule length)" } else { "" };
+            println!("   {} - priority {}{}", candidate.router.name, candidate.priority, note);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the lint findings, exits with 1 when any is more than informational
+async fn run_lint_command(client: &api::TraefikClient) -> anyhow::Result<()> {
+    let data = client.fetch_all_data().await?;
+    let findings = lint::lint(&data);
+
+    for warning in &data.warnings {
+        println!("⚠️  Skipped {} {}: {}", warning.kind, warning.name, warning.message);
+    }
+
+    if findings.is_empty() {
+        println!("✅ No unused or dangling services, routers or middlewares found");
+        return Ok(());
+    }
+
+    for finding in &findings {
+        println!("{} [{}] {}: {}", finding.severity().icon(), finding.badge(), finding.subject, finding.describe());
+    }
+
+    let failing = findings.iter().filter(|f| f.severity() != lint::Severity::Info).count();
+    if failing > 0 {
+        println!("\n❌ {} of {} findings are errors or warnings", failing, findings.len());
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Print the security audit findings, exits with 1 when any is an error
+async fn run_audit_command(client: &api::TraefikClient, config: &audit::AuditConfig) -> anyhow::Result<()> {
+    let data = client.fetch_all_data().await?;
+    let findings = audit::audit(&data, config);
+
+    for warning in &data.warnings {
+        println!("⚠️  Skipped {} {}: {}", warning.kind, warning.name, warning.message);
+    }
+
+    if findings.is_empty() {
+        println!("✅ No risky routing found");
+        return Ok(());
+    }
+
+    for finding in &findings {
+        println!("{} [{}] {}: {}", finding.severity.icon(), finding.rule.id(), finding.router, finding.detail);
+    }
+
+    let errors = findings.iter().filter(|f| f.severity == lint::Severity::Error).count();
+    if errors > 0 {
+        println!("\n❌ {} of {} findings are errors", errors, findings.len());
+        std::process::exit(1);
+    }
+    Ok(())
 }
 
 #[tokio::main]
@@ -98,6 +280,27 @@ async fn main() -> anyhow::Result<()> {
 
     let cli = Cli::parse();
 
+    if let Some(Command::Route { url, method, headers, entrypoint, client_ip }) = cli.command {
+        let client = api::TraefikClient::new(cli.host.clone(), cli.insecure)?;
+        let req = route_match::RouteRequest::new(&method, &url, &headers, entrypoint, client_ip)?;
+        return run_route_command(&client, req).await;
+    }
+
+    if let Some(Command::Lint) = cli.command {
+        let client = api::TraefikClient::new(cli.host.clone(), cli.insecure)?;
+        return run_lint_command(&client).await;
+    }
+
+    let audit_config = match cli.audit_config {
+        Some(ref path) => audit::AuditConfig::load(path)?,
+        None => audit::AuditConfig::default(),
+    };
+
+    if let Some(Command::Audit) = cli.command {
+        let client = api::TraefikClient::new(cli.host.clone(), cli.insecure)?;
+        return run_audit_command(&client, &audit_config).await;
+    }
+
     // If headless flag is set, just fetch and display data
     if cli.headless {
         let client = crate::api::TraefikClient::new(cli.host.clone(), cli.insecure)?;
@@ -114,6 +317,12 @@ async fn main() -> anyhow::Result<()> {
                 if data.routers.len() > 5 {
                     println!("     ... and {} more routers", data.routers.len() - 5);
                 }
+                if !data.warnings.is_empty() {
+                    println!("\n⚠️  Skipped {} entries that could not be parsed:", data.warnings.len());
+                    for warning in &data.warnings {
+                        println!("  {} {}: {}", warning.kind, warning.name, warning.message);
+                    }
+                }
                 
                 println!("\n🎯 The TUI application is working! To run the full interface:");
                 println!("   Run this in your actual terminal (outside Claude Code):");
@@ -142,6 +351,15 @@ async fn main() -> anyhow::Result<()> {
     // Create app (failures here will now properly restore terminal)
     let mut app = App::new(cli.host, cli.insecure, cli.ignore)?;
     app.refresh_interval = Duration::from_secs(cli.refresh);
+    app.namespace_hints = cli.namespaces;
+    app.docker = cli.docker_socket.map(docker::DockerClient::new);
+    app.audit_config = audit_config;
+    app.acme_paths = cli.acme_json;
+    app.cert_warn_days = cli.cert_warn_days;
+    app.migration_export_path = cli.migration_export;
+    app.tls_addresses = cli.tls_inspect;
+    app.probe_addresses = cli.probe;
+    app.server_probe_interval = cli.server_probe_interval.map(Duration::from_secs);
 
     // Initial data fetch
     if let Err(e) = app.refresh_data().await {
@@ -161,8 +379,8 @@ async fn main() -> anyhow::Result<()> {
 
         if crossterm::event::poll(timeout)? {
             if let Event::Key(key) = event::read()? {
-                match app.state {
-                    AppState::Search => {
+                match (&app.view, &app.state) {
+                    (_, AppState::Search) => {
                         match key.code {
                             KeyCode::Esc => {
                                 app.exit_search_mode();
@@ -181,9 +399,26 @@ async fn main() -> anyhow::Result<()> {
                             _ => {}
                         }
                     }
-                    AppState::Filtered => {
+                    (_, AppState::RouteInput) => {
                         match key.code {
                             KeyCode::Esc => {
+                                app.cancel_route_input();
+                            }
+                            KeyCode::Enter => {
+                                app.submit_route_input();
+                            }
+                            KeyCode::Backspace => {
+                                app.route_input.pop();
+                            }
+                            KeyCode::Char(c) => {
+                                app.route_input.push(c);
+                            }
+                            _ => {}
+                        }
+                    }
+                    (View::Routers, _) => {
+                        match key.code {
+                            KeyCode::Esc if app.state == AppState::Filtered => {
                                 app.exit_search_mode();
                             }
                             KeyCode::Char('q') => {
@@ -200,6 +435,32 @@ async fn main() -> anyhow::Result<()> {
                             KeyCode::Char('s') | KeyCode::Char('S') => {
                                 app.toggle_sort_mode();
                             }
+                            KeyCode::Tab => {
+                                app.toggle_group_mode();
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Enter => {
+                                if !app.toggle_selected_group() {
+                                    app.open_selected_service_detail();
+                                }
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Char('i') => {
+                                app.toggle_hide_internal();
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Char('w') => {
+                                app.open_view(View::ParseWarnings);
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Char('T') => {
+                                app.open_view(View::ServersTransports);
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Char('t') => {
+                                app.enter_route_input();
+                                app.pending_g_key = false;
+                            }
                             KeyCode::Up | KeyCode::Char('k') => {
                                 app.previous_router();
                                 app.pending_g_key = false;
@@ -231,13 +492,62 @@ async fn main() -> anyhow::Result<()> {
                             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                                 app.quit();
                             }
+                            KeyCode::Char('c') => {
+                                app.open_view(View::RouteConflicts);
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Char('m') => {
+                                app.open_view(View::Migration);
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Char('a') => {
+                                app.open_view(View::Audit);
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Char('C') => {
+                                app.open_view(View::Certificates);
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Char('H') => {
+                                app.open_view(View::Redirects);
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Char('L') => {
+                                app.open_view(View::Lint);
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Char('v') => {
+                                app.open_view(View::Services);
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Char('p') => {
+                                app.toggle_rule_pretty();
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Left | KeyCode::Char('h') => {
+                                app.scroll_rule(-8);
+                                app.pending_g_key = false;
+                            }
+                            KeyCode::Right | KeyCode::Char('l') => {
+                                app.scroll_rule(8);
+                                app.pending_g_key = false;
+                            }
                             _ => {
                                 app.pending_g_key = false;
                             }
                         }
                     }
-                    _ => {
+                    (View::Services, _) => {
                         match key.code {
+                            KeyCode::Esc | KeyCode::Backspace => {
+                                app.close_view();
+                            }
+                            KeyCode::Enter => {
+                                app.open_selected_service_routers();
+                            }
+                            KeyCode::Char('d') => {
+                                app.toggle_services_down_only();
+                            }
                             KeyCode::Char('q') => {
                                 app.quit();
                             }
@@ -246,49 +556,60 @@ async fn main() -> anyhow::Result<()> {
                                     error!("Failed to refresh data: {}", e);
                                 }
                             }
-                            KeyCode::Char('/') => {
-                                app.enter_search_mode();
-                            }
-                            KeyCode::Char('s') | KeyCode::Char('S') => {
-                                app.toggle_sort_mode();
-                            }
                             KeyCode::Up | KeyCode::Char('k') => {
-                                app.previous_router();
-                                app.pending_g_key = false;
+                                app.move_service_selection(-1);
                             }
                             KeyCode::Down | KeyCode::Char('j') => {
-                                app.next_router();
-                                app.pending_g_key = false;
+                                app.move_service_selection(1);
                             }
-                            KeyCode::Char('g') => {
-                                if app.pending_g_key {
-                                    // Second 'g' - go to first router (gg)
-                                    app.go_to_first_router();
-                                    app.pending_g_key = false;
-                                } else {
-                                    // First 'g' - wait for second one
-                                    app.pending_g_key = true;
+                            KeyCode::PageDown => {
+                                app.move_service_selection(10);
+                            }
+                            KeyCode::PageUp => {
+                                app.move_service_selection(-10);
+                            }
+                            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.quit();
+                            }
+                            _ => {}
+                        }
+                    }
+                    _ => {
+                        // Read-only views (service details, reports)
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Backspace | KeyCode::Enter => {
+                                app.close_view();
+                            }
+                            KeyCode::Char('t') if app.view == View::RouteTest => {
+                                app.enter_route_input();
+                            }
+                            KeyCode::Char('x') if app.view == View::Migration => {
+                                app.export_migrations();
+                            }
+                            KeyCode::Char('q') => {
+                                app.quit();
+                            }
+                            KeyCode::Char('r') | KeyCode::Char('R') => {
+                                if let Err(e) = app.refresh_data().await {
+                                    error!("Failed to refresh data: {}", e);
                                 }
                             }
-                            KeyCode::Char('G') => {
-                                app.go_to_last_router();
-                                app.pending_g_key = false; // Clear any pending g
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                app.scroll_view_up(1);
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                app.scroll_view_down(1);
                             }
                             KeyCode::PageDown => {
-                                app.page_down(10); // Page down by ~10 routers
-                                app.pending_g_key = false;
+                                app.scroll_view_down(10);
                             }
                             KeyCode::PageUp => {
-                                app.page_up(10); // Page up by ~10 routers
-                                app.pending_g_key = false;
+                                app.scroll_view_up(10);
                             }
                             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                                 app.quit();
                             }
-                            _ => {
-                                // Reset pending g key on any other key press
-                                app.pending_g_key = false;
-                            }
+                            _ => {}
                         }
                     }
                 }
@@ -308,6 +629,11 @@ async fn main() -> anyhow::Result<()> {
             }
         }
 
+        app.collect_background();
+        if app.server_probe_due() {
+            app.start_server_probes();
+        }
+
         if app.should_quit {
             break;
         }
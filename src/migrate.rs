@@ -0,0 +1,313 @@
+use crate::rule::{parse, parse_router_rule, v2_template_to_regex, Expr, Matcher, MatcherCall, RuleSyntax};
+use crate::types::Router;
+
+/// Suggested v3 rule for a router still on v2 syntax
+#[derive(Debug, Clone)]
+pub struct Migration<'a> {
+    pub router: &'a Router,
+    /// Whether v2 was assumed because the API didn't report a syntax
+    pub defaulted: bool,
+    /// The v3 rule, None when the v2 rule doesn't parse
+    pub rule: Option<String>,
+    /// Constructs without a direct v3 equivalent that need a manual review
+    pub issues: Vec<String>,
+}
+
+fn call(matcher: Matcher, args: Vec<String>) -> Expr {
+    Expr::Matcher(MatcherCall { matcher, args })
+}
+
+/// The alternatives chained with ||, None for an empty list
+fn any_of(exprs: Vec<Expr>) -> Option<Expr> {
+    exprs.into_iter().reduce(|left, right| Expr::Or(Box::new(left), Box::new(right)))
+}
+
+/// The conditions chained with &&, None for an empty list
+fn all_of(exprs: Vec<Expr>) -> Option<Expr> {
+    exprs.into_iter().reduce(|left, right| Expr::And(Box::new(left), Box::new(right)))
+}
+
+fn has_template(value: &str) -> bool {
+    value.contains('{')
+}
+
+/// Convert a v2 template into an anchored v3 regexp, reporting templates that don't convert
+fn template_regex(template: &str, default_pattern: &str, anchor_end: bool, issues: &mut Vec<String>) -> String {
+    match v2_template_to_regex(template, default_pattern) {
+        Ok(regex) => format!("^{}{}", regex, if anchor_end { "$" } else { "" }),
+        Err(e) => {
+            issues.push(e);
+            template.to_string()
+        }
+    }
+}
+
+fn migrate_call(call_v2: &MatcherCall, issues: &mut Vec<String>) -> Expr {
+    let args = &call_v2.args;
+    let migrated = match call_v2.matcher {
+        Matcher::Host | Matcher::HostHeader | Matcher::HostRegexp => any_of(
+            args.iter()
+                .map(|host| {
+                    if has_template(host) {
+                        issues.push(format!("host template {} became a regexp, v2 matched it case-insensitively", host));
+                        call(Matcher::HostRegexp, vec![template_regex(host, "[^.]+", true, issues)])
+                    } else {
+                        call(Matcher::Host, vec![host.clone()])
+                    }
+                })
+                .collect(),
+        ),
+        Matcher::Path | Matcher::PathPrefix => {
+            let prefix = call_v2.matcher == Matcher::PathPrefix;
+            any_of(
+                args.iter()
+                    .map(|path| {
+                        if has_template(path) {
+                            issues.push(format!("path template {} became a PathRegexp", path));
+                            call(Matcher::PathRegexp, vec![template_regex(path, "[^/]+", !prefix, issues)])
+                        } else {
+                            call(call_v2.matcher, vec![path.clone()])
+                        }
+                    })
+                    .collect(),
+            )
+        }
+        Matcher::Method | Matcher::ClientIP => {
+            any_of(args.iter().map(|arg| call(call_v2.matcher, vec![arg.clone()])).collect())
+        }
+        Matcher::Headers => Some(call(Matcher::Header, args.clone())),
+        Matcher::HeadersRegexp => Some(call(Matcher::HeaderRegexp, args.clone())),
+        Matcher::Query => all_of(
+            args.iter()
+                .map(|pair| match pair.split_once('=') {
+                    Some((key, value)) if has_template(value) => {
+                        issues.push(format!("query template {} became a QueryRegexp", pair));
+                        call(Matcher::QueryRegexp, vec![key.to_string(), template_regex(value, ".*", true, issues)])
+                    }
+                    Some((key, value)) if !value.is_empty() => {
+                        call(Matcher::Query, vec![key.to_string(), value.to_string()])
+                    }
+                    Some((key, _)) => call(Matcher::Query, vec![key.to_string()]),
+                    None => call(Matcher::Query, vec![pair.clone()]),
+                })
+                .collect(),
+        ),
+        Matcher::HostSNI => {
+            issues.push(format!("{} is a TCP matcher with no HTTP equivalent", call_v2.matcher.name()));
+            any_of(args.iter().map(|arg| call(call_v2.matcher, vec![arg.clone()])).collect())
+        }
+        // Only exist in v3, a v2 rule never contains them
+        Matcher::PathRegexp
+        | Matcher::Header
+        | Matcher::HeaderRegexp
+        | Matcher::QueryRegexp
+        | Matcher::HostSNIRegexp
+        | Matcher::Alpn => Some(call(call_v2.matcher, args.clone())),
+    };
+    // The parser rejects v2 matchers without arguments, there is nothing to chain for them
+    migrated.unwrap_or_else(|| Expr::Matcher(call_v2.clone()))
+}
+
+/// Rewrite a parsed v2 rule with v3 matchers
+fn migrate_expr(expr: &Expr, issues: &mut Vec<String>) -> Expr {
+    match expr {
+        Expr::Matcher(call_v2) => migrate_call(call_v2, issues),
+        Expr::Not(inner) => Expr::Not(Box::new(migrate_expr(inner, issues))),
+        // Rebuild chains flat, so expanded matchers don't end up in parentheses
+        Expr::And(left, right) => {
+            let (left, right) = (migrate_expr(left, issues), migrate_expr(right, issues));
+            let chain: Vec<Expr> = operands(left, is_and).into_iter().chain(operands(right, is_and)).collect();
+            all_of(chain).unwrap_or_else(|| expr.clone())
+        }
+        Expr::Or(left, right) => {
+            let (left, right) = (migrate_expr(left, issues), migrate_expr(right, issues));
+            let chain: Vec<Expr> = operands(left, is_or).into_iter().chain(operands(right, is_or)).collect();
+            any_of(chain).unwrap_or_else(|| expr.clone())
+        }
+    }
+}
+
+fn is_and(expr: &Expr) -> bool {
+    matches!(expr, Expr::And(_, _))
+}
+
+fn is_or(expr: &Expr) -> bool {
+    matches!(expr, Expr::Or(_, _))
+}
+
+/// Operands of a chain of the same operator
+fn operands(expr: Expr, same_operator: fn(&Expr) -> bool) -> Vec<Expr> {
+    if !same_operator(&expr) {
+        return vec![expr];
+    }
+    match expr {
+        Expr::And(left, right) | Expr::Or(left, right) => {
+            let mut chain = operands(*left, same_operator);
+            chain.extend(operands(*right, same_operator));
+            chain
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Suggested v3 rules for every router on v2 syntax
+pub fn migrations(routers: &[Router]) -> Vec<Migration<'_>> {
+    routers
+        .iter()
+        .filter_map(|router| {
+            let (syntax, defaulted) = RuleSyntax::of_router(router);
+            if syntax != RuleSyntax::V2 {
+                return None;
+            }
+
+            let mut issues = Vec::new();
+            let rule = match parse_router_rule(router) {
+                Ok(expr) => {
+                    let rule = migrate_expr(&expr, &mut issues).to_string();
+                    if let Err(e) = parse(&rule, RuleSyntax::V3) {
+                        issues.push(format!("suggested rule doesn't parse as v3: {}", e));
+                    }
+                    Some(rule)
+                }
+                Err(e) => {
+                    issues.push(format!("v2 rule doesn't parse: {}", e));
+                    None
+                }
+            };
+
+            Some(Migration { router, defaulted, rule, issues })
+        })
+        .collect()
+}
+
+/// Single-quoted YAML scalar
+fn yaml_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// File provider YAML with the suggested rules, issues are kept as comments
+pub fn migrations_yaml(migrations: &[Migration]) -> String {
+    let mut yaml = String::from("# v3 rules suggested by traefiktop, review before applying\nhttp:\n  routers:\n");
+
+    for migration in migrations {
+        let router = migration.router;
+        // Router names in the file provider carry no @provider suffix, same names from other providers get it appended
+        let bare = |router: &Router| router.name.split('@').next().unwrap_or(&router.name).to_string();
+        let name = bare(router);
+        let shared = migrations.iter().filter(|other| bare(other.router) == name).count() > 1;
+        let name = if shared && !router.provider.is_empty() {
+            format!("{}-{}", name, router.provider)
+        } else {
+            name
+        };
+
+        if router.provider.is_empty() {
+            yaml.push_str(&format!("    # {}\n", router.name));
+        } else {
+            yaml.push_str(&format!("    # {} (provider {})\n", router.name, router.provider));
+        }
+        for issue in &migration.issues {
+            yaml.push_str(&format!("    # REVIEW: {}\n", issue));
+        }
+        match migration.rule {
+            Some(ref rule) => {
+                yaml.push_str(&format!("    {}:\n", yaml_quote(&name)));
+                yaml.push_str("      ruleSyntax: v3\n");
+                yaml.push_str(&format!("      rule: {}\n", yaml_quote(rule)));
+            }
+            None => yaml.push_str(&format!("    # {}: not migrated, rule: {}\n", name, router.rule)),
+        }
+    }
+
+    yaml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router(name: &str, rule: &str, syntax: Option<&str>) -> Router {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "rule": rule,
+            "ruleSyntax": syntax,
+            "service": "app",
+            "provider": "file",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn suggests_v3_rules() {
+        let cases: [(&str, &str, &[&str]); 9] = [
+            ("Host(`a.com`)", "Host(`a.com`)", &[]),
+            ("Host(`a.com`, `b.com`) && PathPrefix(`/api`)", "(Host(`a.com`) || Host(`b.com`)) && PathPrefix(`/api`)", &[]),
+            ("Path(`/a`, `/b`) || Method(`GET`, `HEAD`)", "Path(`/a`) || Path(`/b`) || Method(`GET`) || Method(`HEAD`)", &[]),
+            ("HostHeader(`a.com`) && Headers(`X-Env`, `dev`)", "Host(`a.com`) && Header(`X-Env`, `dev`)", &[]),
+            ("HeadersRegexp(`X-Env`, `^d`)", "HeaderRegexp(`X-Env`, `^d`)", &[]),
+            ("Query(`a=1`, `b=`, `c`)", "Query(`a`, `1`) && Query(`b`) && Query(`c`)", &[]),
+            (
+                "Host(`{sub:[a-z]+}.a.com`)",
+                "HostRegexp(`^(?:[a-z]+)\\.a\\.com$`)",
+                &["host template {sub:[a-z]+}.a.com became a regexp, v2 matched it case-insensitively"],
+            ),
+            ("PathPrefix(`/u/{id}`)", "PathRegexp(`^/u/(?:[^/]+)`)", &["path template /u/{id} became a PathRegexp"]),
+            ("Query(`id={n:[0-9]+}`)", "QueryRegexp(`id`, `^(?:[0-9]+)$`)", &["query template id={n:[0-9]+} became a QueryRegexp"]),
+        ];
+        for (rule, expected, issues) in cases {
+            let routers = [router("r@file", rule, Some("v2"))];
+            let migration = &migrations(&routers)[0];
+            assert_eq!(migration.rule.as_deref(), Some(expected), "{}", rule);
+            assert_eq!(migration.issues, issues, "{}", rule);
+        }
+    }
+
+    #[test]
+    fn only_migrates_v2_routers() {
+        let routers = [
+            router("explicit@file", "Host(`a.com`)", Some("v2")),
+            router("defaulted@file", "Host(`a.com`)", None),
+            router("current@file", "Host(`a.com`)", Some("v3")),
+            router("broken@file", "Host(`a.com`", Some("v2")),
+        ];
+        let migrations = migrations(&routers);
+        let summary: Vec<(&str, bool, bool)> = migrations
+            .iter()
+            .map(|migration| (migration.router.name.as_str(), migration.defaulted, migration.rule.is_some()))
+            .collect();
+        assert_eq!(
+            summary,
+            [("explicit@file", false, true), ("defaulted@file", true, true), ("broken@file", false, false)]
+        );
+        assert_eq!(migrations[2].issues, ["v2 rule doesn't parse: expected ',' or ')', found end of rule at column 13"]);
+    }
+
+    #[test]
+    fn writes_file_provider_yaml() {
+        let mut routers = [
+            router("web@file", "Host(`it's.com`)", Some("v2")),
+            router("web@docker", "Host(`a.com`)", Some("v2")),
+            router("broken@file", "Host(", Some("v2")),
+        ];
+        routers[1].provider = "docker".to_string();
+
+        let yaml = migrations_yaml(&migrations(&routers));
+        let expected = "\
+# v3 rules suggested by traefiktop, review before applying
+http:
+  routers:
+    # web@file (provider file)
+    'web-file':
+      ruleSyntax: v3
+      rule: 'Host(`it''s.com`)'
+    # web@docker (provider docker)
+    'web-docker':
+      ruleSyntax: v3
+      rule: 'Host(`a.com`)'
+    # broken@file (provider file)
+    # REVIEW: v2 rule doesn't parse: expected ',' or ')', found end of rule at column 6
+    # broken: not migrated, rule: Host(
+";
+        assert_eq!(yaml, expected);
+    }
+}
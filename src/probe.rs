@@ -0,0 +1,371 @@
+use crate::grouping::router_provider;
+use crate::redirects::{entry_point_tls, router_entry_points};
+use crate::rule::{parse_router_rule, Expr, Matcher};
+use crate::tls_inspect::EntryPointAddress;
+use crate::types::{EntryPoint, HealthCheck, Router, Service};
+use anyhow::{anyhow, Context, Result};
+use reqwest::redirect::Policy;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Requests in flight at once, a probe round shouldn't look like a flood to the backends
+const MAX_CONCURRENT_PROBES: usize = 16;
+
+/// Methods a probe may send, anything else could change state on the backend
+fn is_safe_method(method: &str) -> bool {
+    method.eq_ignore_ascii_case("GET") || method.eq_ignore_ascii_case("HEAD")
+}
+
+/// A request a router's rule should match, regexp matchers and negations are left out
+#[derive(Debug, Clone, Default)]
+struct ProbeRequest {
+    method: Option<String>,
+    host: Option<String>,
+    path: Option<String>,
+    query: Vec<String>,
+    headers: Vec<(String, String)>,
+}
+
+impl ProbeRequest {
+    /// Fill in what a rule requires, the left side of an alternative is enough
+    fn collect(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Matcher(call) => {
+                let first = call.args.first().cloned();
+                match call.matcher {
+                    Matcher::Host | Matcher::HostHeader if self.host.is_none() => self.host = first,
+                    // v2 paths may hold regexp placeholders like {id:[0-9]+}
+                    Matcher::Path | Matcher::PathPrefix if self.path.is_none() => {
+                        self.path = first.filter(|path| !path.contains('{'));
+                    }
+                    Matcher::Method if self.method.is_none() => {
+                        self.method = call.args.iter().find(|method| is_safe_method(method)).cloned().or(first);
+                    }
+                    Matcher::Header | Matcher::Headers if call.args.len() == 2 => {
+                        self.headers.push((call.args[0].clone(), call.args[1].clone()));
+                    }
+                    // v2 takes `key=value` pairs, v3 a key and a value
+                    Matcher::Query => match call.args.as_slice() {
+                        [key, value] if !key.contains('=') => self.query.push(format!("{}={}", key, value)),
+                        pairs => self.query.extend(pairs.iter().cloned()),
+                    },
+                    _ => {}
+                }
+            }
+            Expr::Not(_) => {}
+            Expr::And(left, right) => {
+                self.collect(left);
+                self.collect(right);
+            }
+            Expr::Or(left, _) => self.collect(left),
+        }
+    }
+
+    fn for_router(router: &Router) -> Self {
+        let mut request = ProbeRequest::default();
+        if let Ok(expr) = parse_router_rule(router) {
+            request.collect(&expr);
+        }
+        request
+    }
+
+    /// Whether the rule can be matched without a method that changes state
+    fn is_safe(&self) -> bool {
+        self.method.as_deref().is_none_or(is_safe_method)
+    }
+}
+
+/// Answer to a probe
+#[derive(Debug, Clone)]
+pub struct ProbeResponse {
+    pub status: u16,
+    pub latency: Duration,
+}
+
+/// A request sent through an entrypoint as a client would send it for a router
+#[derive(Debug, Clone)]
+pub struct ProbeResult {
+    pub router: String,
+    pub entry_point: String,
+    pub method: String,
+    pub url: String,
+    /// The response, or why the request failed
+    pub outcome: Result<ProbeResponse, String>,
+}
+
+impl ProbeResult {
+    /// Not routed (404), a gateway or backend error (5xx) or no answer at all
+    pub fn failed(&self) -> bool {
+        match self.outcome {
+            Ok(ref response) => response.status == 404 || response.status >= 500,
+            Err(_) => true,
+        }
+    }
+
+    /// Short result for router rows, e.g. "502 12ms"
+    pub fn summary(&self) -> String {
+        match self.outcome {
+            Ok(ref response) => format!("{} {}ms", response.status, response.latency.as_millis()),
+            Err(_) => "failed".to_string(),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let result = match self.outcome {
+            Ok(ref response) => {
+                let reason = reqwest::StatusCode::from_u16(response.status)
+                    .ok()
+                    .and_then(|status| status.canonical_reason())
+                    .map(|reason| format!(" {}", reason))
+                    .unwrap_or_default();
+                format!("{}{} in {}ms", response.status, reason, response.latency.as_millis())
+            }
+            Err(ref e) => e.clone(),
+        };
+        format!("{} {} via {} → {}", self.method, self.url, self.entry_point, result)
+    }
+}
+
+/// Innermost error of a failed request, reqwest wraps it in several layers naming the URL again
+fn root_cause(e: &reqwest::Error) -> String {
+    let mut cause: &dyn std::error::Error = e;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    cause.to_string()
+}
+
+/// One client for everything probed through an entrypoint, the hosts resolve to its address whatever they resolve to elsewhere
+async fn entry_point_client(address: &str, hosts: &[&str]) -> Result<reqwest::Client> {
+    let socket_address = tokio::net::lookup_host(address)
+        .await
+        .with_context(|| format!("Failed to resolve {}", address))?
+        .next()
+        .ok_or_else(|| anyhow!("{} doesn't resolve", address))?;
+
+    // Certificates and redirects are reported elsewhere, a probe only checks the route answers
+    let mut builder = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .redirect(Policy::none())
+        .danger_accept_invalid_certs(true)
+        .user_agent("traefiktop-rs/0.1.0");
+    for host in hosts {
+        builder = builder.resolve(host, socket_address);
+    }
+    builder.build().context("Failed to build HTTP client")
+}
+
+async fn send(client: &reqwest::Client, url: &str, method: &str, request: &ProbeRequest, address: &str) -> Result<ProbeResponse> {
+    let method = reqwest::Method::from_bytes(method.as_bytes()).map_err(|_| anyhow!("invalid method {}", method))?;
+    let mut http_request = client.request(method, url);
+    for (name, value) in &request.headers {
+        http_request = http_request.header(name, value);
+    }
+
+    let started = Instant::now();
+    let response = http_request.send().await.map_err(|e| match e {
+        e if e.is_timeout() => anyhow!("no answer within {}s", PROBE_TIMEOUT.as_secs()),
+        e if e.is_connect() => anyhow!("Failed to connect to {}", address),
+        e => anyhow!(root_cause(&e)),
+    })?;
+    Ok(ProbeResponse { status: response.status().as_u16(), latency: started.elapsed() })
+}
+
+/// Probe one router through an entrypoint
+async fn probe_router(
+    router: Router,
+    request: ProbeRequest,
+    address: EntryPointAddress,
+    tls: bool,
+    client: Result<reqwest::Client, String>,
+) -> ProbeResult {
+    // Without a Host matcher any host routes, the entrypoint's address will do.
+    // Resolving the host to the entrypoint keeps the URL's port, so a non-standard one has to be in it.
+    let (scheme, default_port) = if tls { ("https", "443") } else { ("http", "80") };
+    let authority = match (request.host.as_deref(), address.address.rsplit_once(':')) {
+        (Some(host), Some((_, port))) if port != default_port => format!("{}:{}", host, port),
+        (Some(host), _) => host.to_string(),
+        (None, _) => address.address.clone(),
+    };
+    let mut url = format!("{}://{}{}", scheme, authority, request.path.as_deref().unwrap_or("/"));
+    let query: Vec<&str> = request.query.iter().map(|pair| pair.as_str()).filter(|pair| !pair.contains('{')).collect();
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(&query.join("&"));
+    }
+    let method = request.method.clone().unwrap_or_else(|| "GET".to_string()).to_uppercase();
+
+    let outcome = match client {
+        Ok(client) => send(&client, &url, &method, &request, &address.address).await.map_err(|e| format!("{:#}", e)),
+        Err(e) => Err(e),
+    };
+    ProbeResult { router: router.name, entry_point: address.entry_point, method, url, outcome }
+}
+
+/// Probe every router on a probed entrypoint concurrently, through the first one it is on.
+/// Routers only matching methods other than GET and HEAD are left out.
+pub async fn probe_all(routers: &[Router], entry_points: &[EntryPoint], addresses: &[EntryPointAddress]) -> Vec<ProbeResult> {
+    let mut targets: Vec<Vec<(&Router, ProbeRequest)>> = addresses.iter().map(|_| Vec::new()).collect();
+    for router in routers {
+        // Traefik's own routers, like the entrypoint redirections, answer for any request
+        if router_provider(router) == Some("internal") {
+            continue;
+        }
+        let router_eps = router_entry_points(router, entry_points);
+        let Some(index) = addresses.iter().position(|address| router_eps.contains(&address.entry_point.as_str())) else {
+            continue;
+        };
+        let request = ProbeRequest::for_router(router);
+        if request.is_safe() {
+            targets[index].push((router, request));
+        }
+    }
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_PROBES));
+    let mut probes = JoinSet::new();
+    for (address, requests) in addresses.iter().zip(targets) {
+        if requests.is_empty() {
+            continue;
+        }
+        let hosts: Vec<&str> = requests.iter().filter_map(|(_, request)| request.host.as_deref()).collect();
+        let client = entry_point_client(&address.address, &hosts).await.map_err(|e| format!("{:#}", e));
+        for (router, request) in requests {
+            let tls = router.tls.is_some() || entry_point_tls(&address.entry_point, entry_points);
+            let probe = probe_router(router.clone(), request, address.clone(), tls, client.clone());
+            let semaphore = semaphore.clone();
+            probes.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                probe.await
+            });
+        }
+    }
+
+    let mut results = probes.join_all().await;
+    results.sort_by(|a, b| a.router.cmp(&b.router));
+    results
+}
+
+/// A load balancer server requested directly, bypassing Traefik
+#[derive(Debug, Clone)]
+pub struct ServerProbe {
+    pub service: String,
+    /// The server as configured in the load balancer
+    pub server: String,
+    /// Status the health check expects, any 2xx or 3xx otherwise
+    pub expected_status: Option<u16>,
+    pub outcome: Result<ProbeResponse, String>,
+}
+
+impl ServerProbe {
+    pub fn failed(&self) -> bool {
+        match (&self.outcome, self.expected_status) {
+            (Ok(response), Some(expected)) => response.status != expected,
+            (Ok(response), None) => !(200..400).contains(&response.status),
+            (Err(_), _) => true,
+        }
+    }
+
+    /// Short result for server lines, e.g. "200 12ms" or the error
+    pub fn summary(&self) -> String {
+        match self.outcome {
+            Ok(ref response) => format!("{} {}ms", response.status, response.latency.as_millis()),
+            Err(ref e) => e.clone(),
+        }
+    }
+}
+
+/// The server's URL with the health check's scheme, port and path applied
+fn health_check_url(server: &str, health_check: Option<&HealthCheck>) -> Result<reqwest::Url> {
+    let mut url = reqwest::Url::parse(server).with_context(|| format!("invalid server URL {}", server))?;
+    let Some(hc) = health_check else {
+        return Ok(url);
+    };
+    if let Some(ref scheme) = hc.scheme {
+        url.set_scheme(scheme).map_err(|_| anyhow!("invalid health check scheme {}", scheme))?;
+    }
+    if let Some(port) = hc.port {
+        url.set_port(Some(port)).map_err(|_| anyhow!("invalid health check port {}", port))?;
+    }
+    if let Some(ref path) = hc.path {
+        let (path, query) = match path.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (path.as_str(), None),
+        };
+        url.set_path(path);
+        url.set_query(query);
+    }
+    Ok(url)
+}
+
+/// Request a server the way its health check would
+fn server_client(redirect: Policy) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .redirect(redirect)
+        .danger_accept_invalid_certs(true)
+        .user_agent("traefiktop-rs/0.1.0")
+        .build()
+        .context("Failed to build HTTP client")
+}
+
+async fn probe_server(client: &reqwest::Client, server: String, health_check: Option<HealthCheck>) -> Result<ProbeResponse> {
+    let hc = health_check.as_ref();
+    let url = health_check_url(&server, hc)?;
+
+    let method = hc.and_then(|hc| hc.method.as_deref()).unwrap_or("GET");
+    let method = reqwest::Method::from_bytes(method.as_bytes()).map_err(|_| anyhow!("invalid method {}", method))?;
+    let mut request = client.request(method, url);
+    if let Some(hostname) = hc.and_then(|hc| hc.hostname.as_deref()) {
+        request = request.header(reqwest::header::HOST, hostname);
+    }
+    for (name, value) in hc.and_then(|hc| hc.headers.as_ref()).into_iter().flatten() {
+        request = request.header(name, value);
+    }
+
+    let started = Instant::now();
+    let response = request.send().await.map_err(|e| match e {
+        e if e.is_timeout() => anyhow!("no answer within {}s", PROBE_TIMEOUT.as_secs()),
+        e if e.is_connect() => anyhow!("connection failed"),
+        e => anyhow!(root_cause(&e)),
+    })?;
+    Ok(ProbeResponse { status: response.status().as_u16(), latency: started.elapsed() })
+}
+
+/// Request every load balancer server concurrently, gRPC health checks are left out
+pub async fn probe_servers(services: &[Service]) -> Vec<ServerProbe> {
+    // One client per redirect policy, shared by every server so connections and TLS config are reused
+    let following = server_client(Policy::default()).map_err(|e| format!("{:#}", e));
+    let not_following = server_client(Policy::none()).map_err(|e| format!("{:#}", e));
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_PROBES));
+    let mut probes = JoinSet::new();
+    for service in services.iter().filter(|service| service.provider != "internal") {
+        let Some(ref lb) = service.load_balancer else {
+            continue;
+        };
+        let health_check = lb.health_check.clone();
+        if health_check.as_ref().and_then(|hc| hc.mode.as_deref()) == Some("grpc") {
+            continue;
+        }
+        let follow_redirects = health_check.as_ref().and_then(|hc| hc.follow_redirects) != Some(false);
+        let client = if follow_redirects { &following } else { &not_following };
+        for server in &lb.servers {
+            let (service, server, health_check) = (service.name.clone(), server.url.clone(), health_check.clone());
+            let (client, semaphore) = (client.clone(), semaphore.clone());
+            probes.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                let expected_status = health_check.as_ref().and_then(|hc| hc.status);
+                let outcome = match client {
+                    Ok(client) => probe_server(&client, server.clone(), health_check).await.map_err(|e| format!("{:#}", e)),
+                    Err(e) => Err(e),
+                };
+                ServerProbe { service, server, expected_status, outcome }
+            });
+        }
+    }
+    probes.join_all().await
+}
@@ -0,0 +1,151 @@
+use crate::grouping::{router_hosts, router_provider};
+use crate::lint::middleware_types;
+use crate::types::{EntryPoint, Router, TraefikData};
+
+/// Entrypoints of a router, all of them when it doesn't list any
+pub fn router_entry_points<'a>(router: &'a Router, entry_points: &'a [EntryPoint]) -> Vec<&'a str> {
+    if router.entry_points.is_empty() {
+        entry_points.iter().map(|ep| ep.name.as_str()).collect()
+    } else {
+        router.entry_points.iter().map(|ep| ep.as_str()).collect()
+    }
+}
+
+/// Whether the entrypoint applies TLS to every router on it
+pub fn entry_point_tls(name: &str, entry_points: &[EntryPoint]) -> bool {
+    entry_points
+        .iter()
+        .find(|ep| ep.name == name)
+        .and_then(|ep| ep.http.as_ref())
+        .is_some_and(|http| http.tls.is_some())
+}
+
+/// Whether the entrypoint redirects every request to another entrypoint
+pub fn entry_point_redirects(name: &str, entry_points: &[EntryPoint]) -> bool {
+    entry_points
+        .iter()
+        .find(|ep| ep.name == name)
+        .and_then(|ep| ep.http.as_ref())
+        .and_then(|http| http.redirections.as_ref())
+        .is_some_and(|redirections| redirections.entry_point.is_some())
+}
+
+/// How a host is reachable over HTTP and HTTPS
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PairStatus {
+    /// HTTP redirects and HTTPS serves
+    Paired,
+    /// A router answers plain HTTP without redirecting
+    PlainHttp,
+    /// HTTP redirects but nothing serves the host over HTTPS
+    NoHttps,
+    /// Only HTTPS, plain HTTP requests get a 404
+    NoRedirect,
+}
+
+impl PairStatus {
+    /// Short label for router rows, None when nothing is wrong
+    pub fn badge(self) -> Option<&'static str> {
+        match self {
+            PairStatus::PlainHttp => Some("plain http"),
+            PairStatus::NoHttps => Some("no https"),
+            PairStatus::Paired | PairStatus::NoRedirect => None,
+        }
+    }
+}
+
+/// The HTTP and HTTPS routers of one host
+#[derive(Debug, Clone)]
+pub struct HostRedirect {
+    pub host: String,
+    /// Routers answering plain HTTP with a redirect, or `entrypoint <name>` for entrypoint-wide redirections
+    pub redirects: Vec<String>,
+    /// Routers serving plain HTTP
+    pub plain: Vec<String>,
+    /// Routers serving HTTPS
+    pub secure: Vec<String>,
+}
+
+impl HostRedirect {
+    pub fn status(&self) -> PairStatus {
+        if !self.plain.is_empty() {
+            PairStatus::PlainHttp
+        } else if self.secure.is_empty() {
+            PairStatus::NoHttps
+        } else if self.redirects.is_empty() {
+            PairStatus::NoRedirect
+        } else {
+            PairStatus::Paired
+        }
+    }
+
+    /// The routers on the other side of the pair, for a router of this host
+    pub fn counterparts(&self, router: &str) -> Vec<String> {
+        let is_redirect = self.redirects.iter().any(|name| name == router);
+        let is_secure = self.secure.iter().any(|name| name == router);
+        match (is_redirect, is_secure) {
+            (true, false) => self.secure.clone(),
+            (false, true) => self.redirects.clone(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn push_unique(names: &mut Vec<String>, name: &str) {
+    if !names.iter().any(|existing| existing == name) {
+        names.push(name.to_string());
+    }
+}
+
+/// Pair up the HTTP and HTTPS routers of every host, hosts sorted by name
+pub fn pair_redirects(data: &TraefikData) -> Vec<HostRedirect> {
+    let mut hosts: Vec<HostRedirect> = Vec::new();
+
+    for router in &data.routers {
+        // Traefik's own routers, like the entrypoint redirections, match any host
+        if router_provider(router) == Some("internal") {
+            continue;
+        }
+        let redirecting = middleware_types(router, &data.middlewares)
+            .iter()
+            .any(|kind| kind == "redirectscheme");
+
+        // Without entrypoint details only the router's own TLS setting is known
+        let entry_points = router_entry_points(router, &data.entry_points);
+        let entry_points = if entry_points.is_empty() { vec![""] } else { entry_points };
+
+        for host in router_hosts(router) {
+            let index = match hosts.iter().position(|h| h.host == host) {
+                Some(index) => index,
+                None => {
+                    hosts.push(HostRedirect { host, redirects: Vec::new(), plain: Vec::new(), secure: Vec::new() });
+                    hosts.len() - 1
+                }
+            };
+            let pair = &mut hosts[index];
+
+            for entry_point in &entry_points {
+                if router.tls.is_some() || entry_point_tls(entry_point, &data.entry_points) {
+                    push_unique(&mut pair.secure, &router.name);
+                } else if redirecting || entry_point_redirects(entry_point, &data.entry_points) {
+                    push_unique(&mut pair.redirects, &router.name);
+                } else {
+                    push_unique(&mut pair.plain, &router.name);
+                }
+            }
+        }
+    }
+
+    // An entrypoint redirecting everything covers hosts that only have HTTPS routers
+    let redirecting: Vec<&EntryPoint> = data
+        .entry_points
+        .iter()
+        .filter(|ep| entry_point_redirects(&ep.name, &data.entry_points) && !entry_point_tls(&ep.name, &data.entry_points))
+        .collect();
+    for pair in hosts.iter_mut().filter(|pair| pair.redirects.is_empty() && pair.plain.is_empty()) {
+        pair.redirects.extend(redirecting.iter().map(|ep| format!("entrypoint {}", ep.name)));
+    }
+
+    hosts.sort_by(|a, b| a.host.cmp(&b.host));
+    hosts
+}
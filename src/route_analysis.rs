@@ -0,0 +1,409 @@
+use crate::grouping::router_provider;
+use crate::route_match::effective_priority;
+use crate::rule::{parse_router_rule, Expr, Matcher, RuleSyntax};
+use crate::types::Router;
+
+/// Upper bound on the disjunctive normal form size, larger rules are left out of the analysis
+const MAX_CONJUNCTS: usize = 64;
+
+/// A single condition of a rule, normalized so equal conditions compare equal
+#[derive(Debug, Clone, PartialEq)]
+enum Atom {
+    Host(String),
+    Path(String),
+    PathPrefix(String),
+    Method(String),
+    Header(String, String),
+    Query(String, Option<String>),
+    /// Regexps, templates and anything else, compared by their text only
+    Opaque(String),
+}
+
+/// A rule as OR of ANDs
+type Dnf = Vec<Vec<Atom>>;
+
+fn has_template(value: &str) -> bool {
+    value.contains('{')
+}
+
+/// The alternatives a matcher call expands to, each alternative being a conjunction
+fn matcher_dnf(matcher: Matcher, args: &[String], syntax: RuleSyntax) -> Dnf {
+    let opaque = || vec![vec![Atom::Opaque(format!("{}({})", matcher.name(), args.join(",")))]];
+    let any_of = |make: &dyn Fn(&str) -> Atom| args.iter().map(|arg| vec![make(arg)]).collect();
+    let templated = syntax == RuleSyntax::V2 && args.iter().any(|arg| has_template(arg));
+
+    match matcher {
+        Matcher::Host | Matcher::HostHeader | Matcher::HostSNI if !templated => {
+            any_of(&|host| Atom::Host(host.to_ascii_lowercase()))
+        }
+        Matcher::Path if !templated => any_of(&|path| Atom::Path(path.to_string())),
+        Matcher::PathPrefix if !templated => any_of(&|path| Atom::PathPrefix(path.to_string())),
+        Matcher::Method => any_of(&|method| Atom::Method(method.to_ascii_uppercase())),
+        Matcher::Header | Matcher::Headers => {
+            vec![vec![Atom::Header(args[0].to_ascii_lowercase(), args[1].clone())]]
+        }
+        Matcher::Query if !templated => match syntax {
+            // v2 pairs must all match
+            RuleSyntax::V2 => vec![args
+                .iter()
+                .map(|pair| match pair.split_once('=') {
+                    Some((key, value)) => Atom::Query(key.to_string(), Some(value.to_string())),
+                    None => Atom::Query(pair.to_string(), None),
+                })
+                .collect()],
+            RuleSyntax::V3 => vec![vec![Atom::Query(args[0].clone(), args.get(1).cloned())]],
+        },
+        _ => opaque(),
+    }
+}
+
+/// Convert a rule into disjunctive normal form, None for negations or oversized rules
+fn to_dnf(expr: &Expr, syntax: RuleSyntax) -> Option<Dnf> {
+    match expr {
+        Expr::Matcher(call) => Some(matcher_dnf(call.matcher, &call.args, syntax)),
+        // Coverage of negated conditions can't be decided with this representation
+        Expr::Not(_) => None,
+        Expr::Or(left, right) => {
+            let mut dnf = to_dnf(left, syntax)?;
+            dnf.extend(to_dnf(right, syntax)?);
+            (dnf.len() <= MAX_CONJUNCTS).then_some(dnf)
+        }
+        Expr::And(left, right) => {
+            let left = to_dnf(left, syntax)?;
+            let right = to_dnf(right, syntax)?;
+            if left.len() * right.len() > MAX_CONJUNCTS {
+                return None;
+            }
+            Some(
+                left.iter()
+                    .flat_map(|l| right.iter().map(move |r| l.iter().chain(r).cloned().collect()))
+                    .collect(),
+            )
+        }
+    }
+}
+
+/// Whether every request matching `specific` also matches `general`
+fn atom_covers(general: &Atom, specific: &Atom) -> bool {
+    match (general, specific) {
+        (Atom::PathPrefix(prefix), Atom::Path(path) | Atom::PathPrefix(path)) => path.starts_with(prefix.as_str()),
+        (Atom::Query(key, None), Atom::Query(other, _)) => key == other,
+        _ => general == specific,
+    }
+}
+
+/// Whether no request can match both atoms
+fn atoms_conflict(a: &Atom, b: &Atom) -> bool {
+    match (a, b) {
+        (Atom::Host(x), Atom::Host(y)) | (Atom::Path(x), Atom::Path(y)) | (Atom::Method(x), Atom::Method(y)) => x != y,
+        (Atom::Path(path), Atom::PathPrefix(prefix)) | (Atom::PathPrefix(prefix), Atom::Path(path)) => {
+            !path.starts_with(prefix.as_str())
+        }
+        (Atom::PathPrefix(x), Atom::PathPrefix(y)) => !x.starts_with(y.as_str()) && !y.starts_with(x.as_str()),
+        (Atom::Header(k1, v1), Atom::Header(k2, v2)) => k1 == k2 && v1 != v2,
+        (Atom::Query(k1, Some(v1)), Atom::Query(k2, Some(v2))) => k1 == k2 && v1 != v2,
+        _ => false,
+    }
+}
+
+/// A conjunction covers another when each of its conditions is implied by one of the other's
+fn conjunct_covers(general: &[Atom], specific: &[Atom]) -> bool {
+    general.iter().all(|g| specific.iter().any(|s| atom_covers(g, s)))
+}
+
+fn conjuncts_may_overlap(a: &[Atom], b: &[Atom]) -> bool {
+    !a.iter().any(|x| b.iter().any(|y| atoms_conflict(x, y)))
+}
+
+fn dnf_may_overlap(a: &Dnf, b: &Dnf) -> bool {
+    a.iter().any(|ca| b.iter().any(|cb| conjuncts_may_overlap(ca, cb)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteWarningKind {
+    /// Every request the router matches is taken by higher-priority routers
+    Shadowed { by: Vec<String> },
+    /// Routers with equal priority that can match the same request
+    Ambiguous { with: String, priority: i64 },
+    /// The same host is routed by routers from different providers
+    DuplicateHost { host: String, other: String, other_provider: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct RouteWarning {
+    pub router: String,
+    pub kind: RouteWarningKind,
+}
+
+impl RouteWarning {
+    /// Short label for router rows
+    pub fn badge(&self) -> &'static str {
+        match self.kind {
+            RouteWarningKind::Shadowed { .. } => "shadowed",
+            RouteWarningKind::Ambiguous { .. } => "ambiguous",
+            RouteWarningKind::DuplicateHost { .. } => "duplicate host",
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.kind {
+            RouteWarningKind::Shadowed { ref by } => {
+                format!("never matches, fully covered by higher priority {}", by.join(", "))
+            }
+            RouteWarningKind::Ambiguous { ref with, priority } => {
+                format!("overlaps with {} at the same priority {}", with, priority)
+            }
+            RouteWarningKind::DuplicateHost { ref host, ref other, ref other_provider } => {
+                format!("host {} is also routed by {} from provider {}", host, other, other_provider)
+            }
+        }
+    }
+}
+
+struct AnalyzedRouter<'a> {
+    router: &'a Router,
+    dnf: Dnf,
+    priority: i64,
+}
+
+impl AnalyzedRouter<'_> {
+    /// Entrypoints the router listens on, None means all of them
+    fn entry_points(&self) -> Option<&[String]> {
+        Some(self.router.entry_points.as_slice()).filter(|eps| !eps.is_empty())
+    }
+
+    fn listens_on(&self, entrypoint: Option<&str>) -> bool {
+        match (self.entry_points(), entrypoint) {
+            (None, _) => true,
+            (Some(eps), Some(ep)) => eps.iter().any(|e| e == ep),
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Whether both routers can receive the same request: a shared entrypoint and the same TLS-ness
+    fn shares_traffic_with(&self, other: &AnalyzedRouter) -> bool {
+        if self.router.tls.is_some() != other.router.tls.is_some() {
+            return false;
+        }
+        match (self.entry_points(), other.entry_points()) {
+            (Some(a), Some(b)) => a.iter().any(|ep| b.contains(ep)),
+            _ => true,
+        }
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        let mut hosts: Vec<&str> = self
+            .dnf
+            .iter()
+            .flatten()
+            .filter_map(|atom| match atom {
+                Atom::Host(host) => Some(host.as_str()),
+                _ => None,
+            })
+            .collect();
+        hosts.sort();
+        hosts.dedup();
+        hosts
+    }
+}
+
+/// Flag shadowed routers, ambiguous equal-priority overlaps and hosts routed by several providers
+pub fn analyze_routes(routers: &[Router]) -> Vec<RouteWarning> {
+    let analyzed: Vec<AnalyzedRouter> = routers
+        .iter()
+        .filter(|router| router.status != "disabled")
+        .filter_map(|router| {
+            let expr = parse_router_rule(router).ok()?;
+            let dnf = to_dnf(&expr, RuleSyntax::of_router(router).0)?;
+            Some(AnalyzedRouter { router, dnf, priority: effective_priority(router).0 })
+        })
+        .collect();
+
+    let mut warnings = Vec::new();
+
+    for lo in &analyzed {
+        // Fully shadowed: on every entrypoint, each alternative is covered by a higher priority router
+        let higher: Vec<&AnalyzedRouter> = analyzed
+            .iter()
+            .filter(|hi| hi.priority > lo.priority && hi.shares_traffic_with(lo))
+            .collect();
+        let entrypoints: Vec<Option<&str>> = match lo.entry_points() {
+            Some(eps) => eps.iter().map(|ep| Some(ep.as_str())).collect(),
+            None => vec![None],
+        };
+
+        let mut by = Vec::new();
+        let shadowed = !higher.is_empty()
+            && entrypoints.iter().all(|ep| {
+                lo.dnf.iter().all(|conjunct| {
+                    let cover = higher
+                        .iter()
+                        .filter(|hi| hi.listens_on(*ep))
+                        .find(|hi| hi.dnf.iter().any(|general| conjunct_covers(general, conjunct)));
+                    if let Some(hi) = cover {
+                        if !by.contains(&hi.router.name) {
+                            by.push(hi.router.name.clone());
+                        }
+                    }
+                    cover.is_some()
+                })
+            });
+        if shadowed {
+            warnings.push(RouteWarning {
+                router: lo.router.name.clone(),
+                kind: RouteWarningKind::Shadowed { by },
+            });
+        }
+
+        for other in analyzed.iter().filter(|other| other.router.name != lo.router.name) {
+            if !lo.shares_traffic_with(other) || !dnf_may_overlap(&lo.dnf, &other.dnf) {
+                continue;
+            }
+
+            if other.priority == lo.priority {
+                warnings.push(RouteWarning {
+                    router: lo.router.name.clone(),
+                    kind: RouteWarningKind::Ambiguous { with: other.router.name.clone(), priority: lo.priority },
+                });
+            }
+
+            if router_provider(other.router) != router_provider(lo.router) {
+                let other_hosts = other.hosts();
+                for host in lo.hosts().into_iter().filter(|host| other_hosts.contains(host)) {
+                    warnings.push(RouteWarning {
+                        router: lo.router.name.clone(),
+                        kind: RouteWarningKind::DuplicateHost {
+                            host: host.to_string(),
+                            other: other.router.name.clone(),
+                            other_provider: router_provider(other.router).unwrap_or_default().to_string(),
+                        },
+                    });
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule::parse;
+
+    fn host(value: &str) -> Atom {
+        Atom::Host(value.to_string())
+    }
+
+    fn path(value: &str) -> Atom {
+        Atom::Path(value.to_string())
+    }
+
+    fn prefix(value: &str) -> Atom {
+        Atom::PathPrefix(value.to_string())
+    }
+
+    fn router(name: &str, rule: &str, priority: i64, provider: &str) -> Router {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "rule": rule,
+            "ruleSyntax": "v3",
+            "priority": priority,
+            "entryPoints": ["web"],
+            "service": "app",
+            "status": "enabled",
+            "provider": provider,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn converts_rules_to_dnf() {
+        let cases = [
+            ("Host(`A.com`)", RuleSyntax::V3, Some(vec![vec![host("a.com")]])),
+            ("Host(`a.com`, `b.com`)", RuleSyntax::V2, Some(vec![vec![host("a.com")], vec![host("b.com")]])),
+            (
+                "(Host(`a.com`) || Host(`b.com`)) && PathPrefix(`/api`)",
+                RuleSyntax::V3,
+                Some(vec![vec![host("a.com"), prefix("/api")], vec![host("b.com"), prefix("/api")]]),
+            ),
+            (
+                "Method(`get`) && Header(`X-Env`, `dev`) && Query(`a`)",
+                RuleSyntax::V3,
+                Some(vec![vec![
+                    Atom::Method("GET".to_string()),
+                    Atom::Header("x-env".to_string(), "dev".to_string()),
+                    Atom::Query("a".to_string(), None),
+                ]]),
+            ),
+            (
+                "Query(`a=1`, `b`)",
+                RuleSyntax::V2,
+                Some(vec![vec![Atom::Query("a".to_string(), Some("1".to_string())), Atom::Query("b".to_string(), None)]]),
+            ),
+            ("Path(`/u/{id}`)", RuleSyntax::V2, Some(vec![vec![Atom::Opaque("Path(/u/{id})".to_string())]])),
+            ("Path(`/u/{id}`)", RuleSyntax::V3, Some(vec![vec![path("/u/{id}")]])),
+            ("Host(`a.com`) && !Path(`/`)", RuleSyntax::V3, None),
+        ];
+        for (rule, syntax, expected) in cases {
+            let expr = parse(rule, syntax).unwrap();
+            assert_eq!(to_dnf(&expr, syntax), expected, "{}", rule);
+        }
+    }
+
+    #[test]
+    fn gives_up_on_oversized_rules() {
+        let alternatives = |n: usize| (0..n).map(|i| format!("Path(`/{}`)", i)).collect::<Vec<_>>().join(" || ");
+        let rule = format!("({}) && ({})", alternatives(8), alternatives(8));
+        assert_eq!(to_dnf(&parse(&rule, RuleSyntax::V3).unwrap(), RuleSyntax::V3).map(|dnf| dnf.len()), Some(64));
+        let rule = format!("({}) && ({})", alternatives(8), alternatives(9));
+        assert_eq!(to_dnf(&parse(&rule, RuleSyntax::V3).unwrap(), RuleSyntax::V3), None);
+    }
+
+    #[test]
+    fn covers_atoms() {
+        let cases = [
+            (prefix("/api"), path("/api/users"), true),
+            (prefix("/api"), prefix("/api/v1"), true),
+            (prefix("/api/v1"), prefix("/api"), false),
+            (path("/api"), prefix("/api"), false),
+            (host("a.com"), host("a.com"), true),
+            (host("a.com"), host("b.com"), false),
+            (Atom::Query("a".to_string(), None), Atom::Query("a".to_string(), Some("1".to_string())), true),
+            (Atom::Query("a".to_string(), Some("1".to_string())), Atom::Query("a".to_string(), None), false),
+            (Atom::Opaque("x".to_string()), Atom::Opaque("x".to_string()), true),
+        ];
+        for (general, specific, expected) in cases {
+            assert_eq!(atom_covers(&general, &specific), expected, "{:?} covers {:?}", general, specific);
+        }
+    }
+
+    #[test]
+    fn flags_shadowed_ambiguous_and_duplicate_routes() {
+        let routers = [
+            router("catchall@file", "Host(`a.com`)", 100, "file"),
+            router("api@file", "Host(`a.com`) && PathPrefix(`/api`)", 10, "file"),
+            router("left@file", "Host(`b.com`)", 5, "file"),
+            router("right@docker", "Host(`b.com`) && Method(`GET`)", 5, "docker"),
+            router("apart@file", "Host(`c.com`)", 5, "file"),
+            router("site@file", "Host(`d.com`) && Path(`/x`)", 2, ""),
+            router("site@docker", "Host(`d.com`)", 1, ""),
+        ];
+        let warnings: Vec<(String, &str)> =
+            analyze_routes(&routers).iter().map(|w| (w.router.clone(), w.badge())).collect();
+        let expected = [
+            ("api@file", "shadowed"),
+            ("left@file", "ambiguous"),
+            ("left@file", "duplicate host"),
+            ("right@docker", "ambiguous"),
+            ("right@docker", "duplicate host"),
+            ("site@file", "duplicate host"),
+            ("site@docker", "duplicate host"),
+        ];
+        assert_eq!(warnings, expected.map(|(router, badge)| (router.to_string(), badge)));
+
+        // Without the provider field, the provider comes from the name suffix
+        let message = analyze_routes(&routers).into_iter().find(|w| w.router == "site@file").unwrap().describe();
+        assert_eq!(message, "host d.com is also routed by site@docker from provider docker");
+    }
+}
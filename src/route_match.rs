@@ -0,0 +1,440 @@
+use crate::rule::{parse_router_rule, v2_template_to_regex, Expr, Matcher, MatcherCall, RuleSyntax};
+use crate::service_status::build_service_tree;
+use crate::types::{Router, Service};
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use reqwest::Url;
+use std::net::IpAddr;
+
+const KNOWN_METHODS: [&str; 9] = ["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS", "CONNECT", "TRACE"];
+
+/// A standard method in any case, or an uppercase word like PURGE
+fn is_method(token: &str) -> bool {
+    KNOWN_METHODS.iter().any(|method| method.eq_ignore_ascii_case(token))
+        || (!token.is_empty() && token.chars().all(|c| c.is_ascii_uppercase()))
+}
+
+/// A request to test against the router rules
+#[derive(Debug, Clone)]
+pub struct RouteRequest {
+    pub method: String,
+    pub tls: bool,
+    pub host: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub client_ip: Option<IpAddr>,
+    pub entrypoint: Option<String>,
+}
+
+impl RouteRequest {
+    /// Build a request from a URL and "Name: value" headers. A Host header overrides the URL host.
+    pub fn new(
+        method: &str,
+        url: &str,
+        headers: &[String],
+        entrypoint: Option<String>,
+        client_ip: Option<IpAddr>,
+    ) -> Result<Self> {
+        let url = Url::parse(url).with_context(|| format!("Invalid URL {}", url))?;
+
+        let headers = headers
+            .iter()
+            .map(|header| {
+                header
+                    .split_once(':')
+                    .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                    .ok_or_else(|| anyhow!("Invalid header {:?}, expected \"Name: value\"", header))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let host = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("host"))
+            .map(|(_, value)| value.clone())
+            .or_else(|| url.host_str().map(|h| h.to_string()))
+            .ok_or_else(|| anyhow!("URL has no host"))?;
+        // Rules match the host without port
+        let host = match host.rsplit_once(':') {
+            Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) && !name.ends_with(':') => name.to_string(),
+            _ => host,
+        };
+
+        let path = urlencoding::decode(url.path())
+            .map(|p| p.into_owned())
+            .unwrap_or_else(|_| url.path().to_string());
+
+        Ok(Self {
+            method: method.to_ascii_uppercase(),
+            tls: url.scheme() == "https",
+            host: host.to_ascii_lowercase(),
+            path,
+            query: url.query_pairs().map(|(k, v)| (k.into_owned(), v.into_owned())).collect(),
+            headers,
+            client_ip,
+            entrypoint,
+        })
+    }
+
+    /// Parse the interactive prompt: `[METHOD] URL [Name:value]... [@entrypoint]`
+    pub fn from_prompt(input: &str) -> Result<Self> {
+        let mut method = "GET".to_string();
+        let mut url = None;
+        let mut headers = Vec::new();
+        let mut entrypoint = None;
+
+        // A lone word is a host, not a method
+        let tokens: Vec<&str> = input.split_whitespace().collect();
+        for (index, token) in tokens.iter().enumerate() {
+            if let Some(name) = token.strip_prefix('@') {
+                entrypoint = Some(name.to_string());
+            } else if index == 0 && tokens.len() > 1 && is_method(token) {
+                method = token.to_string();
+            } else if url.is_none() {
+                url = Some(if token.contains("://") {
+                    token.to_string()
+                } else {
+                    format!("http://{}", token)
+                });
+            } else {
+                headers.push(token.to_string());
+            }
+        }
+
+        let url = url.ok_or_else(|| anyhow!("Enter a URL, e.g. GET https://example.com/path"))?;
+        Self::new(&method, &url, &headers, entrypoint, None)
+    }
+
+    fn header_values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.headers
+            .iter()
+            .filter(move |(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn query_values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.query
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Priority Traefik uses for a router and whether it was defaulted to the rule length
+pub fn effective_priority(router: &Router) -> (i64, bool) {
+    if router.priority == 0 {
+        (router.rule.len() as i64, true)
+    } else {
+        (router.priority, false)
+    }
+}
+
+fn regex_matches(pattern: &str, value: &str) -> bool {
+    Regex::new(pattern).is_ok_and(|re| re.is_match(value))
+}
+
+/// Full, case-insensitive match of a v2 host template
+fn v2_host_matches(template: &str, host: &str) -> bool {
+    v2_template_to_regex(template, "[^.]+")
+        .is_ok_and(|re| regex_matches(&format!("(?i)^{}$", re), host))
+}
+
+fn v2_path_matches(template: &str, path: &str, prefix: bool) -> bool {
+    v2_template_to_regex(template, "[^/]+")
+        .is_ok_and(|re| regex_matches(&format!("^{}{}", re, if prefix { "" } else { "$" }), path))
+}
+
+fn client_ip_matches(range: &str, ip: IpAddr) -> bool {
+    let (network, bits) = match range.split_once('/') {
+        Some((network, bits)) => (network, bits.parse::<u32>().ok()),
+        None => (range, None),
+    };
+    let Ok(network) = network.trim().parse::<IpAddr>() else {
+        return false;
+    };
+
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let bits = bits.unwrap_or(32).min(32);
+            let mask = if bits == 0 { 0 } else { u32::MAX << (32 - bits) };
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let bits = bits.unwrap_or(128).min(128);
+            let mask = if bits == 0 { 0 } else { u128::MAX << (128 - bits) };
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+fn matcher_matches(call: &MatcherCall, syntax: RuleSyntax, req: &RouteRequest) -> bool {
+    let args = &call.args;
+    match call.matcher {
+        Matcher::Host | Matcher::HostHeader => match syntax {
+            RuleSyntax::V2 => args.iter().any(|host| v2_host_matches(host, &req.host)),
+            RuleSyntax::V3 => args.iter().any(|host| host.eq_ignore_ascii_case(&req.host)),
+        },
+        Matcher::HostRegexp => match syntax {
+            RuleSyntax::V2 => args.iter().any(|host| v2_host_matches(host, &req.host)),
+            RuleSyntax::V3 => args.iter().any(|re| regex_matches(re, &req.host)),
+        },
+        Matcher::Path => match syntax {
+            RuleSyntax::V2 => args.iter().any(|path| v2_path_matches(path, &req.path, false)),
+            RuleSyntax::V3 => args.contains(&req.path),
+        },
+        Matcher::PathPrefix => match syntax {
+            RuleSyntax::V2 => args.iter().any(|path| v2_path_matches(path, &req.path, true)),
+            RuleSyntax::V3 => args.iter().any(|path| req.path.starts_with(path.as_str())),
+        },
+        Matcher::PathRegexp => args.iter().any(|re| regex_matches(re, &req.path)),
+        Matcher::Method => args.iter().any(|method| method.eq_ignore_ascii_case(&req.method)),
+        Matcher::Header | Matcher::Headers => req.header_values(&args[0]).any(|value| value == args[1]),
+        Matcher::HeaderRegexp | Matcher::HeadersRegexp => {
+            req.header_values(&args[0]).any(|value| regex_matches(&args[1], value))
+        }
+        Matcher::Query => match syntax {
+            // v2 takes "key=value" pairs that must all match, values may be templates
+            RuleSyntax::V2 => args.iter().all(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                if value.is_empty() {
+                    return req.query_values(key).next().is_some();
+                }
+                v2_template_to_regex(value, ".*").is_ok_and(|re| {
+                    let re = format!("^{}$", re);
+                    req.query_values(key).any(|actual| regex_matches(&re, actual))
+                })
+            }),
+            RuleSyntax::V3 => match args.get(1) {
+                Some(value) => req.query_values(&args[0]).any(|actual| actual == value),
+                None => req.query_values(&args[0]).next().is_some(),
+            },
+        },
+        Matcher::QueryRegexp => req.query_values(&args[0]).any(|value| regex_matches(&args[1], value)),
+        Matcher::ClientIP => req.client_ip.is_some_and(|ip| args.iter().any(|range| client_ip_matches(range, ip))),
+        Matcher::HostSNI => args.iter().any(|host| host == "*" || host.eq_ignore_ascii_case(&req.host)),
+        Matcher::HostSNIRegexp => args.iter().any(|re| regex_matches(re, &req.host)),
+        // ALPN protocols are negotiated at the TLS layer, which an HTTP request can't express
+        Matcher::Alpn => false,
+    }
+}
+
+/// Evaluate a parsed rule against a request
+pub fn rule_matches(expr: &Expr, syntax: RuleSyntax, req: &RouteRequest) -> bool {
+    match expr {
+        Expr::Matcher(call) => matcher_matches(call, syntax, req),
+        Expr::Not(inner) => !rule_matches(inner, syntax, req),
+        Expr::And(left, right) => rule_matches(left, syntax, req) && rule_matches(right, syntax, req),
+        Expr::Or(left, right) => rule_matches(left, syntax, req) || rule_matches(right, syntax, req),
+    }
+}
+
+/// Whether a router listens where the request arrives: the entrypoint and TLS-ness must fit
+pub fn router_accepts(router: &Router, req: &RouteRequest) -> bool {
+    let entrypoint_ok = match req.entrypoint {
+        Some(ref entrypoint) => router.entry_points.is_empty() || router.entry_points.contains(entrypoint),
+        None => true,
+    };
+    entrypoint_ok && router.tls.is_some() == req.tls && router.status != "disabled"
+}
+
+#[derive(Debug, Clone)]
+pub struct RouteCandidate<'a> {
+    pub router: &'a Router,
+    pub priority: i64,
+    pub priority_defaulted: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct RouteTestResult<'a> {
+    /// Matching routers, the winner first
+    pub matched: Vec<RouteCandidate<'a>>,
+    /// Routers whose rule could not be parsed and were not evaluated
+    pub unparsable: Vec<&'a Router>,
+}
+
+/// Evaluate every router against a request, ordered the way Traefik picks them
+pub fn test_route<'a>(routers: &'a [Router], req: &RouteRequest) -> RouteTestResult<'a> {
+    let mut matched = Vec::new();
+    let mut unparsable = Vec::new();
+
+    for router in routers.iter().filter(|router| router_accepts(router, req)) {
+        let Ok(expr) = parse_router_rule(router) else {
+            unparsable.push(router);
+            continue;
+        };
+        let syntax = RuleSyntax::of_router(router).0;
+        if rule_matches(&expr, syntax, req) {
+            let (priority, priority_defaulted) = effective_priority(router);
+            matched.push(RouteCandidate { router, priority, priority_defaulted });
+        }
+    }
+
+    // Highest priority wins, ties are broken by name
+    matched.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.router.name.cmp(&b.router.name)));
+
+    RouteTestResult { matched, unparsable }
+}
+
+/// Where a router sends requests: the active service path and the healthy servers at its end
+pub fn route_destination(router: &Router, services: &[Service]) -> (Vec<String>, Vec<String>) {
+    let tree = build_service_tree(&router.service, services);
+    let path: Vec<String> = tree.active_path().iter().map(|node| node.name.clone()).collect();
+
+    let servers = tree
+        .active_leaf()
+        .and_then(|leaf| leaf.service)
+        .map(|service| {
+            let lb_servers = service.load_balancer.as_ref().map(|lb| lb.servers.as_slice()).unwrap_or(&[]);
+            lb_servers
+                .iter()
+                .filter(|server| {
+                    // Without serverStatus Traefik has no health information, so every server is used
+                    service
+                        .server_status
+                        .as_ref()
+                        .is_none_or(|status| status.get(&server.url).is_some_and(|s| s == "UP"))
+                })
+                .map(|server| server.url.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    (path, servers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router(name: &str, rule: &str, syntax: &str, priority: i64, tls: bool) -> Router {
+        let mut router = serde_json::json!({
+            "name": name,
+            "rule": rule,
+            "ruleSyntax": syntax,
+            "priority": priority,
+            "entryPoints": ["web", "websecure"],
+            "service": "app",
+            "status": "enabled",
+        });
+        if tls {
+            router["tls"] = serde_json::json!({});
+        }
+        serde_json::from_value(router).unwrap()
+    }
+
+    fn request(prompt: &str) -> RouteRequest {
+        RouteRequest::from_prompt(prompt).unwrap()
+    }
+
+    #[test]
+    fn parses_prompts() {
+        let cases = [
+            ("example.com/a", "GET", "example.com", "/a", None),
+            ("post https://example.com/", "POST", "example.com", "/", None),
+            ("PURGE example.com", "PURGE", "example.com", "/", None),
+            ("localhost", "GET", "localhost", "/", None),
+            ("delete example.com", "DELETE", "example.com", "/", None),
+            ("GET example.com:8080/x%20y Host:other.com @web", "GET", "other.com", "/x y", Some("web")),
+        ];
+        for (prompt, method, host, path, entrypoint) in cases {
+            let req = request(prompt);
+            assert_eq!(
+                (req.method.as_str(), req.host.as_str(), req.path.as_str(), req.entrypoint.as_deref()),
+                (method, host, path, entrypoint),
+                "{}",
+                prompt
+            );
+        }
+        assert!(RouteRequest::from_prompt("GET @web").is_err());
+        assert!(RouteRequest::from_prompt("example.com NoColon").is_err());
+    }
+
+    #[test]
+    fn accepts_by_entrypoint_and_tls() {
+        let plain = router("plain", "Host(`a.com`)", "v3", 0, false);
+        let mut disabled = router("disabled", "Host(`a.com`)", "v3", 0, false);
+        disabled.status = "disabled".to_string();
+        let cases = [
+            (&plain, "http://a.com", true),
+            (&plain, "https://a.com", false),
+            (&plain, "http://a.com @web", true),
+            (&plain, "http://a.com @admin", false),
+            (&router("tls", "Host(`a.com`)", "v3", 0, true), "https://a.com @websecure", true),
+            (&disabled, "http://a.com", false),
+        ];
+        for (router, prompt, expected) in cases {
+            assert_eq!(router_accepts(router, &request(prompt)), expected, "{} {}", router.name, prompt);
+        }
+    }
+
+    #[test]
+    fn matches_rules() {
+        let cases = [
+            ("Host(`a.com`)", "v3", "http://A.com/", true),
+            ("Host(`{sub:[a-z]+}.a.com`)", "v2", "http://x.a.com/", true),
+            ("Host(`{sub:[a-z]+}.a.com`)", "v2", "http://x.y.a.com/", false),
+            ("Host(`a.com`, `b.com`)", "v2", "http://b.com/", true),
+            ("HostRegexp(`^.+\\.a\\.com$`)", "v3", "http://x.a.com/", true),
+            ("Path(`/users/{id:[0-9]+}`)", "v2", "http://a.com/users/42", true),
+            ("Path(`/users/{id:[0-9]+}`)", "v2", "http://a.com/users/bob", false),
+            ("PathPrefix(`/api`)", "v3", "http://a.com/api/v1", true),
+            ("PathRegexp(`^/v[0-9]+/`)", "v3", "http://a.com/v2/x", true),
+            ("Method(`POST`)", "v3", "http://a.com/", false),
+            ("Method(`GET`, `POST`)", "v2", "POST http://a.com/", true),
+            ("Header(`X-Env`, `dev`)", "v3", "http://a.com/ X-Env:dev", true),
+            ("HeaderRegexp(`X-Env`, `^d`)", "v3", "http://a.com/ X-Env:prod", false),
+            ("Query(`debug`)", "v3", "http://a.com/?debug", true),
+            ("Query(`a=1`, `b=2`)", "v2", "http://a.com/?a=1", false),
+            ("Query(`a`, `1`)", "v3", "http://a.com/?a=1&b=2", true),
+            ("Host(`a.com`) && !PathPrefix(`/admin`)", "v3", "http://a.com/admin/x", false),
+            ("Host(`b.com`) || PathPrefix(`/admin`)", "v3", "http://a.com/admin/x", true),
+            ("ClientIP(`10.0.0.0/8`)", "v3", "http://a.com/", false),
+        ];
+        for (rule, syntax, prompt, expected) in cases {
+            let router = router("r", rule, syntax, 0, false);
+            let matched = !test_route(std::slice::from_ref(&router), &request(prompt)).matched.is_empty();
+            assert_eq!(matched, expected, "{} ({}) against {}", rule, syntax, prompt);
+        }
+    }
+
+    #[test]
+    fn matches_client_ip_ranges() {
+        let cases = [
+            ("10.0.0.0/8", "10.1.2.3", true),
+            ("10.0.0.0/8", "11.0.0.1", false),
+            ("192.168.1.7", "192.168.1.7", true),
+            ("0.0.0.0/0", "8.8.8.8", true),
+            ("2001:db8::/32", "2001:db8::1", true),
+            ("2001:db8::/32", "10.0.0.1", false),
+            ("nope", "10.0.0.1", false),
+        ];
+        for (range, ip, expected) in cases {
+            assert_eq!(client_ip_matches(range, ip.parse().unwrap()), expected, "{} {}", range, ip);
+        }
+    }
+
+    #[test]
+    fn orders_matches_by_priority() {
+        let routers = [
+            router("short", "PathPrefix(`/`)", "v3", 0, false),
+            router("long", "Host(`a.com`) && PathPrefix(`/api`)", "v3", 0, false),
+            router("pinned", "Host(`a.com`)", "v3", 1000, false),
+            router("tie", "Host(`a.com`)", "v3", 1000, false),
+            router("broken", "Host(`a.com`", "v3", 0, false),
+            router("other", "Host(`b.com`)", "v3", 0, false),
+        ];
+        let result = test_route(&routers, &request("http://a.com/api"));
+        let order: Vec<(&str, i64, bool)> = result
+            .matched
+            .iter()
+            .map(|candidate| (candidate.router.name.as_str(), candidate.priority, candidate.priority_defaulted))
+            .collect();
+        assert_eq!(
+            order,
+            [("pinned", 1000, false), ("tie", 1000, false), ("long", 35, true), ("short", 15, true)]
+        );
+        let unparsable: Vec<&str> = result.unparsable.iter().map(|router| router.name.as_str()).collect();
+        assert_eq!(unparsable, ["broken"]);
+    }
+}
@@ -0,0 +1,668 @@
+use crate::types::Router;
+use std::fmt;
+
+/// Rule syntax of a router, selected by `Router.rule_syntax`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleSyntax {
+    V2,
+    V3,
+}
+
+impl RuleSyntax {
+    /// Syntax of a router's rule, and whether it was defaulted because the API didn't report one.
+    /// Traefik v2 doesn't report `ruleSyntax` at all, so a missing value means v2.
+    pub fn of_router(router: &Router) -> (RuleSyntax, bool) {
+        match router.rule_syntax.as_deref().map(|s| s.to_ascii_lowercase()) {
+            Some(ref s) if s == "v2" => (RuleSyntax::V2, false),
+            Some(ref s) if s == "v3" => (RuleSyntax::V3, false),
+            _ => (RuleSyntax::V2, true),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RuleSyntax::V2 => "v2",
+            RuleSyntax::V3 => "v3",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Matcher {
+    Host,
+    HostHeader,
+    HostRegexp,
+    Path,
+    PathPrefix,
+    PathRegexp,
+    Method,
+    Header,
+    HeaderRegexp,
+    Headers,
+    HeadersRegexp,
+    Query,
+    QueryRegexp,
+    ClientIP,
+    HostSNI,
+    HostSNIRegexp,
+    Alpn,
+}
+
+/// Number of arguments a matcher accepts
+#[derive(Debug, Clone, Copy)]
+enum Arity {
+    Exactly(usize),
+    Between(usize, usize),
+    AtLeast(usize),
+}
+
+const ALL_MATCHERS: [Matcher; 17] = [
+    Matcher::Host,
+    Matcher::HostHeader,
+    Matcher::HostRegexp,
+    Matcher::Path,
+    Matcher::PathPrefix,
+    Matcher::PathRegexp,
+    Matcher::Method,
+    Matcher::Header,
+    Matcher::HeaderRegexp,
+    Matcher::Headers,
+    Matcher::HeadersRegexp,
+    Matcher::Query,
+    Matcher::QueryRegexp,
+    Matcher::ClientIP,
+    Matcher::HostSNI,
+    Matcher::HostSNIRegexp,
+    Matcher::Alpn,
+];
+
+impl Matcher {
+    pub fn name(self) -> &'static str {
+        match self {
+            Matcher::Host => "Host",
+            Matcher::HostHeader => "HostHeader",
+            Matcher::HostRegexp => "HostRegexp",
+            Matcher::Path => "Path",
+            Matcher::PathPrefix => "PathPrefix",
+            Matcher::PathRegexp => "PathRegexp",
+            Matcher::Method => "Method",
+            Matcher::Header => "Header",
+            Matcher::HeaderRegexp => "HeaderRegexp",
+            Matcher::Headers => "Headers",
+            Matcher::HeadersRegexp => "HeadersRegexp",
+            Matcher::Query => "Query",
+            Matcher::QueryRegexp => "QueryRegexp",
+            Matcher::ClientIP => "ClientIP",
+            Matcher::HostSNI => "HostSNI",
+            Matcher::HostSNIRegexp => "HostSNIRegexp",
+            Matcher::Alpn => "ALPN",
+        }
+    }
+
+    /// Matchers are spelled as documented or all lowercase
+    pub fn from_name(name: &str) -> Option<Matcher> {
+        ALL_MATCHERS
+            .into_iter()
+            .find(|matcher| matcher.name() == name || matcher.name().to_ascii_lowercase() == name)
+    }
+
+    pub fn available_in(self, syntax: RuleSyntax) -> bool {
+        match self {
+            Matcher::HostHeader | Matcher::Headers | Matcher::HeadersRegexp => syntax == RuleSyntax::V2,
+            Matcher::PathRegexp
+            | Matcher::Header
+            | Matcher::HeaderRegexp
+            | Matcher::QueryRegexp
+            | Matcher::HostSNIRegexp
+            | Matcher::Alpn => syntax == RuleSyntax::V3,
+            _ => true,
+        }
+    }
+
+    fn arity(self, syntax: RuleSyntax) -> Arity {
+        match (self, syntax) {
+            (Matcher::Header | Matcher::HeaderRegexp | Matcher::Headers | Matcher::HeadersRegexp, _) => Arity::Exactly(2),
+            (Matcher::QueryRegexp, _) => Arity::Exactly(2),
+            (Matcher::Query, RuleSyntax::V3) => Arity::Between(1, 2),
+            // v2 matchers take a list of alternatives, v3 matchers take a single value
+            (_, RuleSyntax::V2) => Arity::AtLeast(1),
+            (_, RuleSyntax::V3) => Arity::Exactly(1),
+        }
+    }
+}
+
+/// A matcher call such as Host(`example.com`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatcherCall {
+    pub matcher: Matcher,
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Matcher(MatcherCall),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Or(_, _) => 1,
+            Expr::And(_, _) => 2,
+            Expr::Not(_) | Expr::Matcher(_) => 3,
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>, min_precedence: u8) -> fmt::Result {
+        if self.precedence() < min_precedence {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+/// Quote a matcher argument, backticks unless the value itself contains one
+pub fn quote(value: &str) -> String {
+    if value.contains('`') {
+        format!("{:?}", value)
+    } else {
+        format!("`{}`", value)
+    }
+}
+
+impl fmt::Display for MatcherCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let args: Vec<String> = self.args.iter().map(|arg| quote(arg)).collect();
+        write!(f, "{}({})", self.matcher.name(), args.join(", "))
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Matcher(call) => write!(f, "{}", call),
+            Expr::Not(inner) => {
+                write!(f, "!")?;
+                inner.fmt_operand(f, 3)
+            }
+            Expr::And(left, right) => {
+                left.fmt_operand(f, 2)?;
+                write!(f, " && ")?;
+                right.fmt_operand(f, 3)
+            }
+            Expr::Or(left, right) => {
+                left.fmt_operand(f, 1)?;
+                write!(f, " || ")?;
+                right.fmt_operand(f, 2)
+            }
+        }
+    }
+}
+
+/// A parse failure, `column` is the 1-based character column in the rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at column {}", self.message, self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Ident,
+    String,
+    LParen,
+    RParen,
+    Comma,
+    And,
+    Or,
+    Not,
+    Whitespace,
+    /// Unterminated string or a character that can't appear in a rule
+    Invalid,
+}
+
+/// A lexical token with its byte span in the rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Split a rule into tokens. Never fails, invalid input becomes `TokenKind::Invalid`
+/// so partial rules can still be highlighted.
+pub fn tokenize(rule: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = rule.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let kind = match c {
+            c if c.is_whitespace() => {
+                while chars.peek().is_some_and(|(_, c)| c.is_whitespace()) {
+                    chars.next();
+                }
+                TokenKind::Whitespace
+            }
+            '(' => TokenKind::LParen,
+            ')' => TokenKind::RParen,
+            ',' => TokenKind::Comma,
+            '!' => TokenKind::Not,
+            '&' | '|' => {
+                if chars.peek().is_some_and(|(_, next)| *next == c) {
+                    chars.next();
+                    if c == '&' { TokenKind::And } else { TokenKind::Or }
+                } else {
+                    TokenKind::Invalid
+                }
+            }
+            '`' => {
+                // Raw string, no escapes
+                if chars.by_ref().any(|(_, c)| c == '`') {
+                    TokenKind::String
+                } else {
+                    TokenKind::Invalid
+                }
+            }
+            '"' => {
+                let mut terminated = false;
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => {
+                            terminated = true;
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                if terminated { TokenKind::String } else { TokenKind::Invalid }
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                while chars.peek().is_some_and(|(_, c)| c.is_alphanumeric() || *c == '_') {
+                    chars.next();
+                }
+                TokenKind::Ident
+            }
+            _ => TokenKind::Invalid,
+        };
+
+        let end = chars.peek().map_or(rule.len(), |(i, _)| *i);
+        tokens.push(Token { kind, start, end });
+    }
+
+    tokens
+}
+
+/// Value of a string token without quotes, with escapes of double-quoted strings resolved
+fn unquote(text: &str) -> String {
+    if let Some(raw) = text.strip_prefix('`') {
+        return raw.strip_suffix('`').unwrap_or(raw).to_string();
+    }
+
+    let inner = text.strip_prefix('"').unwrap_or(text);
+    let inner = inner.strip_suffix('"').unwrap_or(inner);
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+struct Parser<'a> {
+    rule: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    syntax: RuleSyntax,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, offset: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            column: self.rule[..offset.min(self.rule.len())].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn text(&self, token: &Token) -> &'a str {
+        &self.rule[token.start..token.end]
+    }
+
+    /// Offset used for errors at the current position
+    fn here(&self) -> usize {
+        self.peek().map_or(self.rule.len(), |t| t.start)
+    }
+
+    fn describe(&self, token: Option<&Token>) -> String {
+        match token {
+            None => "end of rule".to_string(),
+            Some(t) if t.kind == TokenKind::Invalid && self.text(t).starts_with(['`', '"']) => {
+                "unterminated string".to_string()
+            }
+            Some(t) => format!("'{}'", self.text(t)),
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind, what: &str) -> Result<Token, ParseError> {
+        match self.peek() {
+            Some(token) if token.kind == kind => {
+                let token = token.clone();
+                self.pos += 1;
+                Ok(token)
+            }
+            other => Err(self.error(self.here(), format!("expected {}, found {}", what, self.describe(other)))),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.parse_and()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::Or) {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.parse_unary()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::And) {
+            self.pos += 1;
+            let right = self.parse_unary()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        match self.peek().map(|t| t.kind) {
+            Some(TokenKind::Not) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(TokenKind::LParen) => {
+                self.pos += 1;
+                let inner = self.parse_or()?;
+                self.expect(TokenKind::RParen, "')'")?;
+                Ok(inner)
+            }
+            Some(TokenKind::Ident) => self.parse_matcher(),
+            _ => Err(self.error(
+                self.here(),
+                format!("expected a matcher, '(' or '!', found {}", self.describe(self.peek())),
+            )),
+        }
+    }
+
+    fn parse_matcher(&mut self) -> Result<Expr, ParseError> {
+        let name_token = self.expect(TokenKind::Ident, "a matcher")?;
+        let name = self.text(&name_token);
+
+        let matcher = Matcher::from_name(name)
+            .ok_or_else(|| self.error(name_token.start, format!("unknown matcher '{}'", name)))?;
+        if !matcher.available_in(self.syntax) {
+            return Err(self.error(
+                name_token.start,
+                format!("{} is not available in {} rule syntax", matcher.name(), self.syntax.name()),
+            ));
+        }
+
+        self.expect(TokenKind::LParen, &format!("'(' after {}", matcher.name()))?;
+
+        let mut args = Vec::new();
+        if self.peek().is_some_and(|t| t.kind != TokenKind::RParen) {
+            loop {
+                let arg = self.expect(TokenKind::String, "a quoted string argument")?;
+                args.push(unquote(self.text(&arg)));
+                if self.peek().is_some_and(|t| t.kind == TokenKind::Comma) {
+                    self.pos += 1;
+                } else {
+                    break;
+                }
+            }
+        }
+        self.expect(TokenKind::RParen, "',' or ')'")?;
+
+        let arity_ok = match matcher.arity(self.syntax) {
+            Arity::Exactly(n) => args.len() == n,
+            Arity::Between(min, max) => (min..=max).contains(&args.len()),
+            Arity::AtLeast(n) => args.len() >= n,
+        };
+        if !arity_ok {
+            let expected = match matcher.arity(self.syntax) {
+                Arity::Exactly(1) => "1 argument".to_string(),
+                Arity::Exactly(n) => format!("{} arguments", n),
+                Arity::Between(min, max) => format!("{} or {} arguments", min, max),
+                Arity::AtLeast(n) => format!("at least {} argument{}", n, if n == 1 { "" } else { "s" }),
+            };
+            return Err(self.error(
+                name_token.start,
+                format!("{} takes {} in {} syntax, got {}", matcher.name(), expected, self.syntax.name(), args.len()),
+            ));
+        }
+
+        Ok(Expr::Matcher(MatcherCall { matcher, args }))
+    }
+}
+
+/// Convert a v2 template such as `{sub:[a-z]+}.example.com` into an unanchored regex.
+/// Variables without a pattern match `default_pattern`.
+pub fn v2_template_to_regex(template: &str, default_pattern: &str) -> Result<String, String> {
+    let mut regex = String::new();
+    let mut literal = String::new();
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '}' {
+            return Err(format!("unbalanced '}}' in template {}", template));
+        }
+        if c != '{' {
+            literal.push(c);
+            continue;
+        }
+
+        regex.push_str(&regex::escape(&literal));
+        literal.clear();
+
+        // Read the variable up to its matching brace, patterns may contain braces themselves
+        let mut depth = 1;
+        let mut variable = String::new();
+        for c in chars.by_ref() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                break;
+            }
+            variable.push(c);
+        }
+        if depth != 0 {
+            return Err(format!("unterminated variable in template {}", template));
+        }
+
+        let pattern = match variable.split_once(':') {
+            Some((_, pattern)) if !pattern.is_empty() => pattern,
+            _ => default_pattern,
+        };
+        regex.push_str(&format!("(?:{})", pattern));
+    }
+
+    regex.push_str(&regex::escape(&literal));
+    Ok(regex)
+}
+
+/// Parse a rule in the given syntax
+pub fn parse(rule: &str, syntax: RuleSyntax) -> Result<Expr, ParseError> {
+    let tokens: Vec<Token> = tokenize(rule)
+        .into_iter()
+        .filter(|t| t.kind != TokenKind::Whitespace)
+        .collect();
+
+    let mut parser = Parser { rule, tokens, pos: 0, syntax };
+
+    if parser.tokens.is_empty() {
+        return Err(parser.error(0, "empty rule"));
+    }
+
+    let expr = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+        let message = format!("unexpected {}", parser.describe(Some(token)));
+        return Err(parser.error(token.start, message));
+    }
+
+    Ok(expr)
+}
+
+/// Parse a router's rule in the syntax it declares
+pub fn parse_router_rule(router: &Router) -> Result<Expr, ParseError> {
+    parse(&router.rule, RuleSyntax::of_router(router).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(matcher: Matcher, args: &[&str]) -> Expr {
+        Expr::Matcher(MatcherCall { matcher, args: args.iter().map(|arg| arg.to_string()).collect() })
+    }
+
+    fn and(left: Expr, right: Expr) -> Expr {
+        Expr::And(Box::new(left), Box::new(right))
+    }
+
+    fn or(left: Expr, right: Expr) -> Expr {
+        Expr::Or(Box::new(left), Box::new(right))
+    }
+
+    #[test]
+    fn parses_v2_rules() {
+        let cases = [
+            ("Host(`a.com`)", call(Matcher::Host, &["a.com"])),
+            ("Host(`a.com`, `b.com`)", call(Matcher::Host, &["a.com", "b.com"])),
+            ("host(`a.com`)", call(Matcher::Host, &["a.com"])),
+            ("HostHeader(\"a.com\")", call(Matcher::HostHeader, &["a.com"])),
+            ("Headers(`X-Foo`, `bar`)", call(Matcher::Headers, &["X-Foo", "bar"])),
+            ("Query(`a=1`, `b=2`)", call(Matcher::Query, &["a=1", "b=2"])),
+            (
+                "Host(`a.com`) && PathPrefix(`/api`) || Path(`/`)",
+                or(and(call(Matcher::Host, &["a.com"]), call(Matcher::PathPrefix, &["/api"])), call(Matcher::Path, &["/"])),
+            ),
+            (
+                "Host(`a.com`) && (Path(`/a`) || Path(`/b`))",
+                and(call(Matcher::Host, &["a.com"]), or(call(Matcher::Path, &["/a"]), call(Matcher::Path, &["/b"]))),
+            ),
+            ("!Method(`POST`)", Expr::Not(Box::new(call(Matcher::Method, &["POST"])))),
+        ];
+        for (rule, expected) in cases {
+            assert_eq!(parse(rule, RuleSyntax::V2), Ok(expected), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn parses_v3_rules() {
+        let cases = [
+            ("Host(`a.com`)", call(Matcher::Host, &["a.com"])),
+            ("Header(`X-Foo`, `bar`)", call(Matcher::Header, &["X-Foo", "bar"])),
+            ("Query(`debug`)", call(Matcher::Query, &["debug"])),
+            ("Query(`a`, `1`)", call(Matcher::Query, &["a", "1"])),
+            ("PathRegexp(`^/v[0-9]+`)", call(Matcher::PathRegexp, &["^/v[0-9]+"])),
+            ("HostSNIRegexp(`^.+\\.com$`)", call(Matcher::HostSNIRegexp, &["^.+\\.com$"])),
+            ("ALPN(`h2`)", call(Matcher::Alpn, &["h2"])),
+            ("Path(\"/a\\\"b\")", call(Matcher::Path, &["/a\"b"])),
+        ];
+        for (rule, expected) in cases {
+            assert_eq!(parse(rule, RuleSyntax::V3), Ok(expected), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn displays_parsed_rules() {
+        let cases = [
+            ("Host(`a.com`) && (Path(`/a`) || Path(`/b`))", "Host(`a.com`) && (Path(`/a`) || Path(`/b`))"),
+            ("(Host(`a.com`) && Path(`/a`)) || Path(`/b`)", "Host(`a.com`) && Path(`/a`) || Path(`/b`)"),
+            ("!(Path(`/a`) || Path(`/b`))", "!(Path(`/a`) || Path(`/b`))"),
+            ("Path(\"/a`b\")", "Path(\"/a`b\")"),
+        ];
+        for (rule, expected) in cases {
+            assert_eq!(parse(rule, RuleSyntax::V3).unwrap().to_string(), expected, "{}", rule);
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_arity() {
+        let cases = [
+            ("Host(`a.com`, `b.com`)", RuleSyntax::V3, "Host takes 1 argument in v3 syntax, got 2"),
+            ("Host()", RuleSyntax::V2, "Host takes at least 1 argument in v2 syntax, got 0"),
+            ("Header(`X-Foo`)", RuleSyntax::V3, "Header takes 2 arguments in v3 syntax, got 1"),
+            ("Query(`a`, `b`, `c`)", RuleSyntax::V3, "Query takes 1 or 2 arguments in v3 syntax, got 3"),
+        ];
+        for (rule, syntax, message) in cases {
+            let error = parse(rule, syntax).unwrap_err();
+            assert_eq!(error.message, message, "{}", rule);
+            assert_eq!(error.column, 1, "{}", rule);
+        }
+    }
+
+    #[test]
+    fn reports_error_columns() {
+        let cases = [
+            ("", RuleSyntax::V3, 1, "empty rule"),
+            ("Host(`a.com`) && Nope(`x`)", RuleSyntax::V3, 18, "unknown matcher 'Nope'"),
+            ("HOST(`a.com`)", RuleSyntax::V3, 1, "unknown matcher 'HOST'"),
+            ("Host(`a.com`) && HostHeader(`a.com`)", RuleSyntax::V3, 18, "HostHeader is not available in v3 rule syntax"),
+            ("ALPN(`h2`)", RuleSyntax::V2, 1, "ALPN is not available in v2 rule syntax"),
+            ("Host(`a.com`", RuleSyntax::V3, 13, "expected ',' or ')', found end of rule"),
+            ("Host(`a.com) && Path(`/`)", RuleSyntax::V3, 23, "expected ',' or ')', found '/'"),
+            ("Host(`a.com`) & Path(`/`)", RuleSyntax::V3, 15, "unexpected '&'"),
+            ("Host(a.com)", RuleSyntax::V3, 6, "expected a quoted string argument, found 'a'"),
+            ("Path(`/é`) &&", RuleSyntax::V3, 14, "expected a matcher, '(' or '!', found end of rule"),
+        ];
+        for (rule, syntax, column, message) in cases {
+            let error = parse(rule, syntax).unwrap_err();
+            assert_eq!((error.column, error.message.as_str()), (column, message), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn converts_v2_templates() {
+        let cases = [
+            ("{sub:[a-z]+}.example.com", Ok(r"(?:[a-z]+)\.example\.com")),
+            ("/users/{id}", Ok("/users/(?:[^/]+)")),
+            ("/n/{id:[0-9]{2}}", Ok("/n/(?:[0-9]{2})")),
+            ("/a}", Err("unbalanced '}' in template /a}".to_string())),
+            ("/{id", Err("unterminated variable in template /{id".to_string())),
+        ];
+        for (template, expected) in cases {
+            assert_eq!(v2_template_to_regex(template, "[^/]+"), expected.map(str::to_string), "{}", template);
+        }
+    }
+}
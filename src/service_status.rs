@@ -1,5 +1,4 @@
-use crate::types::{Service, Router};
-use std::collections::HashSet;
+use crate::types::{Router, ServersTransport, Service};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ServiceStatus {
@@ -8,10 +7,64 @@ pub enum ServiceStatus {
     Unknown,
 }
 
+/// What kind of service a tree node represents
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    LoadBalancer,
+    Failover,
+    Weighted,
+    Mirroring,
+    /// Referenced service does not exist
+    Missing,
+    /// Service is already on the current path, recursion stops here
+    Cycle,
+}
+
+/// How a node is referenced by its parent
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeRole {
+    Root,
+    Primary,
+    Fallback,
+    Weight(Option<i64>),
+    MirrorMain,
+    Mirror(Option<u32>),
+}
+
+/// A service and everything it delegates to, with per-node status
 #[derive(Debug, Clone)]
-pub struct FailoverServices<'a> {
-    pub primary: Option<&'a Service>,
-    pub fallback: Option<&'a Service>,
+pub struct ServiceNode<'a> {
+    pub name: String,
+    pub service: Option<&'a Service>,
+    pub kind: NodeKind,
+    pub role: NodeRole,
+    pub status: ServiceStatus,
+    /// Whether requests currently flow through this node
+    pub active: bool,
+    pub children: Vec<ServiceNode<'a>>,
+}
+
+impl<'a> ServiceNode<'a> {
+    /// Follow the active path down to the service that actually answers requests
+    pub fn active_leaf(&self) -> Option<&ServiceNode<'a>> {
+        self.active_path().last().copied()
+    }
+
+    /// Nodes from this one down to the active leaf
+    pub fn active_path(&self) -> Vec<&ServiceNode<'a>> {
+        let mut path = Vec::new();
+        let mut node = Some(self).filter(|node| node.active);
+        while let Some(current) = node {
+            path.push(current);
+            node = current.children.iter().find(|child| child.active);
+        }
+        path
+    }
+
+    /// Whether a cycle was found anywhere below this node
+    pub fn has_cycle(&self) -> bool {
+        self.kind == NodeKind::Cycle || self.children.iter().any(|child| child.has_cycle())
+    }
 }
 
 /// Find service by name, supporting provider suffixes (e.g. service@provider)
@@ -33,91 +86,243 @@ pub fn find_service_by_name<'a>(target_name: &str, services: &'a [Service]) -> O
     })
 }
 
-/// Get failover services for a given failover service
-pub fn get_failover_services<'a>(
-    service_name: &str,
-    services: &'a [Service],
-) -> FailoverServices<'a> {
-    let failover_service = services.iter().find(|s| s.name == service_name);
-    
-    if let Some(service) = failover_service {
-        if let Some(ref failover_config) = service.failover {
-            let primary = find_service_by_name(&failover_config.service, services);
-            let fallback = find_service_by_name(&failover_config.fallback, services);
-            
-            return FailoverServices { primary, fallback };
-        }
-    }
-    
-    FailoverServices {
-        primary: None,
-        fallback: None,
+/// Name of the serversTransport a load balancer service uses, Traefik falls back to `default@internal`
+pub fn servers_transport_name(service: &Service) -> String {
+    let name = service
+        .load_balancer
+        .as_ref()
+        .and_then(|lb| lb.servers_transport.clone())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "default@internal".to_string());
+
+    // Unqualified names refer to a transport from the service's own provider
+    if name.contains('@') || service.provider.is_empty() {
+        name
+    } else {
+        format!("{}@{}", name, service.provider)
     }
 }
 
-/// Get the status of a service, handling failover recursively
-pub fn get_service_status(
-    service: &Service,
-    all_services: &[Service],
-    visited_services: &mut HashSet<String>,
-) -> ServiceStatus {
-    if visited_services.contains(&service.name) {
-        // Circular dependency detected, break recursion
-        return ServiceStatus::Unknown;
-    }
-    
-    visited_services.insert(service.name.clone());
-    
-    let result = if service.service_type.as_deref() == Some("failover") || service.failover.is_some() {
-        // Handle failover service
-        let FailoverServices { primary, fallback } = get_failover_services(&service.name, all_services);
-        
-        let primary_status = if let Some(primary) = primary {
-            get_service_status(primary, all_services, visited_services)
-        } else {
-            ServiceStatus::Unknown
+/// Find the serversTransport a load balancer service uses
+pub fn find_servers_transport<'a>(service: &Service, transports: &'a [ServersTransport]) -> Option<&'a ServersTransport> {
+    let name = servers_transport_name(service);
+    transports.iter().find(|t| t.name == name).or_else(|| {
+        // Traefik v3 may report names without the provider suffix
+        let bare = name.split('@').next().unwrap_or(&name);
+        transports.iter().find(|t| t.name == bare)
+    })
+}
+
+/// Check whether a service is a failover service
+pub fn is_failover(service: &Service) -> bool {
+    service.service_type.as_deref() == Some("failover") || service.failover.is_some()
+}
+
+/// Check whether a service is a mirroring service
+pub fn is_mirroring(service: &Service) -> bool {
+    service.service_type.as_deref() == Some("mirroring") || service.mirroring.is_some()
+}
+
+/// Check whether a service is a weighted round robin service
+pub fn is_weighted(service: &Service) -> bool {
+    service.service_type.as_deref() == Some("weighted") || service.weighted.is_some()
+}
+
+/// Whether any server of a load balancer service is reported DOWN
+pub fn has_down_server(service: &Service) -> bool {
+    service
+        .server_status
+        .as_ref()
+        .is_some_and(|status_map| status_map.values().any(|status| status == "DOWN"))
+}
+
+/// Build the full composition tree for a service name, detecting cycles and marking the active path
+pub fn build_service_tree<'a>(service_name: &str, services: &'a [Service]) -> ServiceNode<'a> {
+    let mut node = build_node(service_name, NodeRole::Root, services, &mut Vec::new());
+    mark_active(&mut node);
+    node
+}
+
+fn build_node<'a>(
+    service_name: &str,
+    role: NodeRole,
+    services: &'a [Service],
+    path: &mut Vec<String>,
+) -> ServiceNode<'a> {
+    let Some(service) = find_service_by_name(service_name, services) else {
+        return ServiceNode {
+            name: service_name.to_string(),
+            service: None,
+            kind: NodeKind::Missing,
+            role,
+            status: ServiceStatus::Unknown,
+            active: false,
+            children: Vec::new(),
         };
-        
-        if primary_status == ServiceStatus::Up {
-            ServiceStatus::Up
-        } else {
-            let fallback_status = if let Some(fallback) = fallback {
-                get_service_status(fallback, all_services, visited_services)
-            } else {
-                ServiceStatus::Unknown
-            };
-            
-            if fallback_status == ServiceStatus::Up {
+    };
+
+    if path.contains(&service.name) {
+        // Circular dependency detected, break recursion but keep it visible
+        return ServiceNode {
+            name: service.name.clone(),
+            service: Some(service),
+            kind: NodeKind::Cycle,
+            role,
+            status: ServiceStatus::Unknown,
+            active: false,
+            children: Vec::new(),
+        };
+    }
+
+    path.push(service.name.clone());
+
+    let (kind, children) = if is_failover(service) {
+        let children = service
+            .failover
+            .as_ref()
+            .map(|config| {
+                vec![
+                    build_node(&config.service, NodeRole::Primary, services, path),
+                    build_node(&config.fallback, NodeRole::Fallback, services, path),
+                ]
+            })
+            .unwrap_or_default();
+        (NodeKind::Failover, children)
+    } else if is_weighted(service) {
+        let children = service
+            .weighted
+            .as_ref()
+            .and_then(|config| config.services.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|weighted| build_node(&weighted.name, NodeRole::Weight(weighted.weight), services, path))
+            .collect();
+        (NodeKind::Weighted, children)
+    } else if is_mirroring(service) {
+        let children = service
+            .mirroring
+            .as_ref()
+            .map(|config| {
+                let mut children = vec![build_node(&config.service, NodeRole::MirrorMain, services, path)];
+                children.extend(
+                    config
+                        .mirrors
+                        .iter()
+                        .flatten()
+                        .map(|mirror| build_node(&mirror.name, NodeRole::Mirror(mirror.percent), services, path)),
+                );
+                children
+            })
+            .unwrap_or_default();
+        (NodeKind::Mirroring, children)
+    } else {
+        (NodeKind::LoadBalancer, Vec::new())
+    };
+
+    path.pop();
+
+    let status = match kind {
+        NodeKind::Failover => {
+            let primary = children.iter().find(|c| c.role == NodeRole::Primary).map(|c| &c.status);
+            let fallback = children.iter().find(|c| c.role == NodeRole::Fallback).map(|c| &c.status);
+
+            if primary == Some(&ServiceStatus::Up) || fallback == Some(&ServiceStatus::Up) {
                 ServiceStatus::Up
-            } else if primary_status == ServiceStatus::Down || fallback_status == ServiceStatus::Down {
+            } else if primary == Some(&ServiceStatus::Down) || fallback == Some(&ServiceStatus::Down) {
                 ServiceStatus::Down
             } else {
                 ServiceStatus::Unknown
             }
         }
-    } else {
-        // Handle regular service
-        if let Some(ref server_status) = service.server_status {
-            let statuses: Vec<&String> = server_status.values().collect();
-            if statuses.iter().any(|status| *status == "UP") {
+        NodeKind::Weighted => {
+            // Traffic is spread over the healthy children, so one is enough, weight 0 children get none
+            let weighted = || children.iter().filter(|c| c.role != NodeRole::Weight(Some(0)));
+            if weighted().any(|c| c.status == ServiceStatus::Up) {
                 ServiceStatus::Up
-            } else if !statuses.is_empty() {
+            } else if weighted().any(|c| c.status == ServiceStatus::Down) {
                 ServiceStatus::Down
             } else {
                 ServiceStatus::Unknown
             }
+        }
+        NodeKind::Mirroring => {
+            // Only the main service answers requests, mirrors don't affect status
+            children
+                .iter()
+                .find(|c| c.role == NodeRole::MirrorMain)
+                .map(|c| c.status.clone())
+                .unwrap_or(ServiceStatus::Unknown)
+        }
+        _ => load_balancer_status(service),
+    };
+
+    ServiceNode {
+        name: service.name.clone(),
+        service: Some(service),
+        kind,
+        role,
+        status,
+        active: false,
+        children,
+    }
+}
+
+/// Status of a plain load balancer service based on its servers
+fn load_balancer_status(service: &Service) -> ServiceStatus {
+    if let Some(ref server_status) = service.server_status {
+        let statuses: Vec<&String> = server_status.values().collect();
+        if statuses.iter().any(|status| *status == "UP") {
+            ServiceStatus::Up
+        } else if !statuses.is_empty() {
+            ServiceStatus::Down
         } else {
-            // If no serverStatus, check the service's enabled/disabled status
-            match service.status.as_str() {
-                "enabled" => ServiceStatus::Up,
-                "disabled" => ServiceStatus::Down,
-                _ => ServiceStatus::Unknown,
+            ServiceStatus::Unknown
+        }
+    } else {
+        // If no serverStatus, check the service's enabled/disabled status
+        match service.status.as_str() {
+            "enabled" => ServiceStatus::Up,
+            "disabled" => ServiceStatus::Down,
+            _ => ServiceStatus::Unknown,
+        }
+    }
+}
+
+/// Mark the nodes requests actually flow through, starting from a healthy root
+fn mark_active(node: &mut ServiceNode) {
+    if node.status != ServiceStatus::Up {
+        return;
+    }
+    node.active = true;
+
+    match node.kind {
+        NodeKind::Failover => {
+            // Primary wins whenever it is healthy, otherwise the fallback takes over
+            let target = [NodeRole::Primary, NodeRole::Fallback].into_iter().find(|role| {
+                node.children
+                    .iter()
+                    .any(|c| &c.role == role && c.status == ServiceStatus::Up)
+            });
+            if let Some(role) = target {
+                if let Some(child) = node.children.iter_mut().find(|c| c.role == role) {
+                    mark_active(child);
+                }
             }
         }
-    };
-    
-    visited_services.remove(&service.name);
-    result
+        NodeKind::Weighted => {
+            // A weight of 0 takes no traffic, a missing weight defaults to 1
+            for child in node.children.iter_mut().filter(|c| c.role != NodeRole::Weight(Some(0))) {
+                mark_active(child);
+            }
+        }
+        NodeKind::Mirroring => {
+            // Mirrors only receive copies of the traffic
+            if let Some(main) = node.children.iter_mut().find(|c| c.role == NodeRole::MirrorMain) {
+                mark_active(main);
+            }
+        }
+        _ => {}
+    }
 }
 
 /// Get all services that match a router's service name (with provider suffix support)
@@ -140,39 +345,11 @@ pub fn get_router_status_info<'a>(router: &Router, services: &'a [Service]) -> (
     let mut active_service: Option<&Service> = None;
     
     for svc in &router_services {
-        if svc.service_type.as_deref() == Some("failover") || svc.failover.is_some() {
-            let FailoverServices { primary, fallback } = get_failover_services(&svc.name, services);
-            
-            let primary_status = if let Some(primary) = primary {
-                get_service_status(primary, services, &mut HashSet::new())
-            } else {
-                ServiceStatus::Unknown
-            };
-            
-            let fallback_status = if let Some(fallback) = fallback {
-                get_service_status(fallback, services, &mut HashSet::new())
-            } else {
-                ServiceStatus::Unknown
-            };
-            
-            if primary_status == ServiceStatus::Up {
-                alive_count += 1;
-                if active_service.is_none() {
-                    active_service = primary;
-                }
-            } else if fallback_status == ServiceStatus::Up {
-                alive_count += 1;
-                if active_service.is_none() {
-                    active_service = fallback;
-                }
-            }
-        } else {
-            let status = get_service_status(svc, services, &mut HashSet::new());
-            if status == ServiceStatus::Up {
-                alive_count += 1;
-                if active_service.is_none() {
-                    active_service = Some(svc);
-                }
+        let tree = build_service_tree(&svc.name, services);
+        if tree.status == ServiceStatus::Up {
+            alive_count += 1;
+            if active_service.is_none() {
+                active_service = tree.active_leaf().and_then(|leaf| leaf.service);
             }
         }
     }
@@ -186,4 +363,55 @@ pub fn get_router_status_info<'a>(router: &Router, services: &'a [Service]) -> (
     };
     
     (router_status, active_service, alive_count)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_balancer(name: &str, server_status: &str) -> Service {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "status": "enabled",
+            "loadBalancer": { "servers": [{ "url": "http://10.0.0.1" }] },
+            "serverStatus": { "http://10.0.0.1": server_status },
+        }))
+        .unwrap()
+    }
+
+    fn weighted(children: &[(&str, Option<i64>)]) -> Service {
+        let services: Vec<_> = children
+            .iter()
+            .map(|(name, weight)| serde_json::json!({ "name": name, "weight": weight }))
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "name": "wrr",
+            "status": "enabled",
+            "type": "weighted",
+            "weighted": { "services": services },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn rolls_up_weighted_status() {
+        let cases = [
+            (("UP", Some(1)), ("DOWN", Some(1)), ServiceStatus::Up, vec!["wrr", "a"]),
+            (("UP", None), ("DOWN", Some(1)), ServiceStatus::Up, vec!["wrr", "a"]),
+            (("DOWN", Some(1)), ("DOWN", Some(1)), ServiceStatus::Down, vec![]),
+            (("UP", Some(0)), ("DOWN", Some(1)), ServiceStatus::Down, vec![]),
+            (("UP", Some(0)), ("UP", Some(2)), ServiceStatus::Up, vec!["wrr", "b"]),
+        ];
+        for ((a_status, a_weight), (b_status, b_weight), expected, path) in cases {
+            let services = vec![
+                weighted(&[("a", a_weight), ("b", b_weight)]),
+                load_balancer("a", a_status),
+                load_balancer("b", b_status),
+            ];
+            let tree = build_service_tree("wrr", &services);
+            let active: Vec<_> = tree.active_path().iter().map(|node| node.name.as_str()).collect();
+            assert_eq!(tree.status, expected, "a={} w={:?}, b={} w={:?}", a_status, a_weight, b_status, b_weight);
+            assert_eq!(active, path, "a={} w={:?}, b={} w={:?}", a_status, a_weight, b_status, b_weight);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::certs::CertInfo;
+use crate::grouping::router_hosts;
+use crate::redirects::{entry_point_tls, router_entry_points};
+use crate::types::{EntryPoint, Router};
+use anyhow::{anyhow, Context, Result};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio_rustls::TlsConnector;
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Handshakes in flight at once
+const MAX_CONCURRENT_HANDSHAKES: usize = 16;
+
+/// Common name of the self-signed certificate Traefik serves when no certificate matches the SNI
+const TRAEFIK_DEFAULT_CERT: &str = "TRAEFIK DEFAULT CERT";
+
+/// Where an entrypoint can be reached from here, given as `name=host:port`
+#[derive(Debug, Clone)]
+pub struct EntryPointAddress {
+    pub entry_point: String,
+    pub address: String,
+}
+
+impl EntryPointAddress {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.split_once('=') {
+            Some((entry_point, address)) if !entry_point.is_empty() && address.contains(':') => Ok(Self {
+                entry_point: entry_point.to_string(),
+                address: address.to_string(),
+            }),
+            _ => Err(format!("expected <entrypoint>=<host>:<port>, got {}", value)),
+        }
+    }
+}
+
+/// Accepts any certificate, the handshake is only made to look at what the server presents
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// TLS client that accepts whatever certificate it is presented
+pub fn connector() -> Result<TlsConnector> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .context("Failed to set up TLS")?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyCert(provider)))
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+/// Handshake with `address` using `host` as SNI and parse the leaf certificate it presents
+async fn inspect(connector: TlsConnector, address: &str, host: &str) -> Result<CertInfo> {
+    let server_name = ServerName::try_from(host.to_string()).map_err(|_| anyhow!("{} is not a valid SNI name", host))?;
+    let handshake = async {
+        let stream = TcpStream::connect(address).await.with_context(|| format!("Failed to connect to {}", address))?;
+        connector.connect(server_name, stream).await.context("TLS handshake failed")
+    };
+    let tls = tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake)
+        .await
+        .map_err(|_| anyhow!("no TLS handshake with {} within {}s", address, HANDSHAKE_TIMEOUT.as_secs()))??;
+
+    let leaf = tls
+        .get_ref()
+        .1
+        .peer_certificates()
+        .and_then(|certs| certs.first())
+        .ok_or_else(|| anyhow!("server presented no certificate"))?;
+    CertInfo::from_der(leaf)
+}
+
+/// The certificate an entrypoint serves for one host
+#[derive(Debug, Clone)]
+pub struct ServedCert {
+    pub host: String,
+    pub entry_point: String,
+    pub address: String,
+    /// The certificate, or why the handshake failed
+    pub result: Result<CertInfo, String>,
+}
+
+/// What an entrypoint serves for a host, worst first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ServedStatus {
+    Failed,
+    /// Traefik's self-signed certificate, nothing matched the SNI
+    DefaultCert,
+    /// A certificate for other hosts
+    Mismatch,
+    Expired,
+    Expiring,
+    Valid,
+}
+
+impl ServedCert {
+    pub fn status(&self, warn_days: i64, now: i64) -> ServedStatus {
+        match self.result {
+            Err(_) => ServedStatus::Failed,
+            Ok(ref cert) if cert.subject == TRAEFIK_DEFAULT_CERT => ServedStatus::DefaultCert,
+            Ok(ref cert) if !cert.covers(&self.host) => ServedStatus::Mismatch,
+            Ok(ref cert) if cert.days_left(now) < 0 => ServedStatus::Expired,
+            Ok(ref cert) if cert.days_left(now) <= warn_days => ServedStatus::Expiring,
+            Ok(_) => ServedStatus::Valid,
+        }
+    }
+
+    /// Short label for router rows, None when nothing needs attention
+    pub fn badge(&self, warn_days: i64, now: i64) -> Option<String> {
+        match self.status(warn_days, now) {
+            ServedStatus::Failed => Some("tls failed".to_string()),
+            ServedStatus::DefaultCert => Some("default cert".to_string()),
+            ServedStatus::Mismatch => Some("wrong cert".to_string()),
+            ServedStatus::Expired => Some("cert expired".to_string()),
+            ServedStatus::Expiring => {
+                let days_left = self.result.as_ref().map_or(0, |cert| cert.days_left(now));
+                Some(format!("cert expires in {}d", days_left))
+            }
+            ServedStatus::Valid => None,
+        }
+    }
+
+    pub fn describe(&self, now: i64) -> String {
+        match self.result {
+            Err(ref e) => e.clone(),
+            Ok(ref cert) if cert.subject == TRAEFIK_DEFAULT_CERT => {
+                "Traefik's default certificate is served, no certificate matches the host".to_string()
+            }
+            Ok(ref cert) if !cert.covers(&self.host) => format!("served certificate is for {}", cert.domains.join(", ")),
+            Ok(ref cert) if cert.days_left(now) < 0 => format!("served certificate expired {}", cert.expires),
+            Ok(ref cert) => format!("served certificate expires {}", cert.expires),
+        }
+    }
+
+    /// Whether this is one of the hosts the router is served for
+    pub fn serves(&self, router: &Router, entry_points: &[EntryPoint]) -> bool {
+        (router.tls.is_some() || entry_point_tls(&self.entry_point, entry_points))
+            && router_entry_points(router, entry_points).contains(&self.entry_point.as_str())
+            && router_hosts(router).contains(&self.host)
+    }
+}
+
+/// Hosts of the TLS routers on each inspected entrypoint, regexp hosts can't be used as SNI
+fn inspection_targets(routers: &[Router], entry_points: &[EntryPoint], addresses: &[EntryPointAddress]) -> Vec<(String, EntryPointAddress)> {
+    let mut targets: Vec<(String, EntryPointAddress)> = Vec::new();
+    for router in routers {
+        for address in addresses {
+            if !router_entry_points(router, entry_points).contains(&address.entry_point.as_str()) {
+                continue;
+            }
+            if router.tls.is_none() && !entry_point_tls(&address.entry_point, entry_points) {
+                continue;
+            }
+            for host in router_hosts(router).into_iter().filter(|host| !host.starts_with('~')) {
+                if !targets.iter().any(|(h, a)| *h == host && a.entry_point == address.entry_point) {
+                    targets.push((host, address.clone()));
+                }
+            }
+        }
+    }
+    targets
+}
+
+/// Handshake with every TLS host on the inspected entrypoints concurrently, sorted by host
+pub async fn inspect_all(
+    connector: TlsConnector,
+    routers: &[Router],
+    entry_points: &[EntryPoint],
+    addresses: &[EntryPointAddress],
+) -> Vec<ServedCert> {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_HANDSHAKES));
+    let mut handshakes = JoinSet::new();
+    for (host, address) in inspection_targets(routers, entry_points, addresses) {
+        let (connector, semaphore) = (connector.clone(), semaphore.clone());
+        handshakes.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = inspect(connector, &address.address, &host).await.map_err(|e| format!("{:#}", e));
+            ServedCert { host, entry_point: address.entry_point, address: address.address, result }
+        });
+    }
+
+    let mut served = handshakes.join_all().await;
+    served.sort_by(|a, b| a.host.cmp(&b.host).then_with(|| a.entry_point.cmp(&b.entry_point)));
+    served
+}
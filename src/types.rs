@@ -1,26 +1,88 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 
+/// Durations are strings ("10s") in Traefik v2 and nanosecond integers in v3, keep them as display strings
+fn duration_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(match value {
+        Some(serde_json::Value::String(s)) => Some(s),
+        Some(serde_json::Value::Number(n)) => n.as_i64().map(format_nanos),
+        _ => None,
+    })
+}
+
+fn format_nanos(nanos: i64) -> String {
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+    if nanos % 1_000 != 0 {
+        return format!("{}ns", nanos);
+    }
+    if nanos % 1_000_000 != 0 {
+        return format!("{}µs", nanos / 1_000);
+    }
+    if nanos % 1_000_000_000 != 0 {
+        return format!("{}ms", nanos / 1_000_000);
+    }
+
+    let total_secs = nanos / 1_000_000_000;
+    let (hours, minutes, secs) = (total_secs / 3600, (total_secs % 3600) / 60, total_secs % 60);
+    let mut out = String::new();
+    if hours > 0 {
+        out.push_str(&format!("{}h", hours));
+    }
+    if minutes > 0 {
+        out.push_str(&format!("{}m", minutes));
+    }
+    if secs > 0 {
+        out.push_str(&format!("{}s", secs));
+    }
+    out
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Router {
-    #[serde(rename = "entryPoints")]
+    #[serde(rename = "entryPoints", default)]
     pub entry_points: Vec<String>,
     pub middlewares: Option<Vec<String>>,
+    #[serde(default)]
     pub service: String,
+    #[serde(default)]
     pub rule: String,
+    #[serde(default)]
     pub priority: i64, // Changed to i64 for very large priorities
     pub tls: Option<TlsConfig>,
+    #[serde(default)]
     pub status: String,
+    #[serde(default)]
     pub using: Vec<String>,
     pub name: String,
+    #[serde(default)]
     pub provider: String,
     #[serde(rename = "ruleSyntax")]
     pub rule_syntax: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TlsConfig {
     pub options: String,
+    #[serde(rename = "certResolver")]
+    pub cert_resolver: Option<String>,
+    /// Domains the resolver requests certificates for, instead of the rule's hosts
+    pub domains: Vec<TlsDomain>,
+    /// TCP routers only, the TLS connection is forwarded to the backend as is
+    pub passthrough: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsDomain {
+    pub main: String,
+    pub sans: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,12 +90,16 @@ pub struct Service {
     #[serde(rename = "loadBalancer")]
     pub load_balancer: Option<LoadBalancer>,
     pub failover: Option<FailoverConfig>,
+    pub mirroring: Option<MirroringConfig>,
+    pub weighted: Option<WeightedConfig>,
+    #[serde(default)]
     pub status: String,
     #[serde(rename = "serverStatus")]
     pub server_status: Option<HashMap<String, String>>,
     #[serde(rename = "usedBy")]
     pub used_by: Option<Vec<String>>,
     pub name: String,
+    #[serde(default)]
     pub provider: String,
     #[serde(rename = "type")]
     pub service_type: Option<String>,
@@ -41,22 +107,65 @@ pub struct Service {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoadBalancer {
+    #[serde(default)]
     pub servers: Vec<Server>,
     #[serde(rename = "healthCheck")]
     pub health_check: Option<HealthCheck>,
+    pub sticky: Option<Sticky>,
+    #[serde(rename = "passHostHeader")]
+    pub pass_host_header: Option<bool>,
+    #[serde(rename = "serversTransport")]
+    pub servers_transport: Option<String>,
+    #[serde(rename = "responseForwarding")]
+    pub response_forwarding: Option<ResponseForwarding>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Server {
     pub url: String,
+    pub weight: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthCheck {
-    pub mode: String,
-    pub path: String,
-    pub interval: String,
-    pub timeout: String,
+    pub scheme: Option<String>,
+    pub mode: Option<String>,
+    pub path: Option<String>,
+    pub method: Option<String>,
+    pub status: Option<u16>,
+    pub port: Option<u16>,
+    #[serde(default, deserialize_with = "duration_string")]
+    pub interval: Option<String>,
+    #[serde(default, deserialize_with = "duration_string")]
+    pub timeout: Option<String>,
+    pub hostname: Option<String>,
+    #[serde(rename = "followRedirects")]
+    pub follow_redirects: Option<bool>,
+    pub headers: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sticky {
+    pub cookie: Option<StickyCookie>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StickyCookie {
+    pub name: Option<String>,
+    pub secure: Option<bool>,
+    #[serde(rename = "httpOnly")]
+    pub http_only: Option<bool>,
+    #[serde(rename = "sameSite")]
+    pub same_site: Option<String>,
+    #[serde(rename = "maxAge")]
+    pub max_age: Option<i64>,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseForwarding {
+    #[serde(rename = "flushInterval", default, deserialize_with = "duration_string")]
+    pub flush_interval: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,8 +174,170 @@ pub struct FailoverConfig {
     pub fallback: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirroringConfig {
+    pub service: String,
+    pub mirrors: Option<Vec<Mirror>>,
+    #[serde(rename = "maxBodySize")]
+    pub max_body_size: Option<i64>,
+    #[serde(rename = "mirrorBody")]
+    pub mirror_body: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mirror {
+    pub name: String,
+    pub percent: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightedConfig {
+    pub services: Option<Vec<WeightedService>>,
+    pub sticky: Option<Sticky>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightedService {
+    pub name: String,
+    pub weight: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Middleware {
+    pub name: String,
+    #[serde(default)]
+    pub provider: String,
+    #[serde(default)]
+    pub status: String,
+    #[serde(rename = "type")]
+    pub middleware_type: Option<String>,
+    #[serde(rename = "usedBy")]
+    pub used_by: Option<Vec<String>>,
+    pub chain: Option<ChainConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainConfig {
+    #[serde(default)]
+    pub middlewares: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryPoint {
+    pub name: String,
+    #[serde(default)]
+    pub address: String,
+    pub http: Option<EntryPointHttp>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryPointHttp {
+    pub redirections: Option<EntryPointRedirections>,
+    /// Default TLS for every router on the entrypoint, only its presence matters here
+    pub tls: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryPointRedirections {
+    #[serde(rename = "entryPoint")]
+    pub entry_point: Option<EntryPointRedirect>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryPointRedirect {
+    #[serde(default)]
+    pub to: String,
+    pub scheme: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServersTransport {
+    pub name: String,
+    #[serde(default)]
+    pub provider: String,
+    #[serde(rename = "serverName")]
+    pub server_name: Option<String>,
+    #[serde(rename = "insecureSkipVerify")]
+    pub insecure_skip_verify: Option<bool>,
+    #[serde(rename = "rootCAs")]
+    pub root_cas: Option<Vec<String>>,
+    pub certificates: Option<Vec<TransportCertificate>>,
+    #[serde(rename = "maxIdleConnsPerHost")]
+    pub max_idle_conns_per_host: Option<i64>,
+    #[serde(rename = "forwardingTimeouts")]
+    pub forwarding_timeouts: Option<ForwardingTimeouts>,
+    #[serde(rename = "disableHTTP2")]
+    pub disable_http2: Option<bool>,
+    #[serde(rename = "peerCertURI")]
+    pub peer_cert_uri: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransportCertificate {
+    #[serde(rename = "certFile")]
+    pub cert_file: Option<String>,
+    #[serde(rename = "keyFile")]
+    pub key_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForwardingTimeouts {
+    #[serde(rename = "dialTimeout", default, deserialize_with = "duration_string")]
+    pub dial_timeout: Option<String>,
+    #[serde(rename = "responseHeaderTimeout", default, deserialize_with = "duration_string")]
+    pub response_header_timeout: Option<String>,
+    #[serde(rename = "idleConnTimeout", default, deserialize_with = "duration_string")]
+    pub idle_conn_timeout: Option<String>,
+    #[serde(rename = "readIdleTimeout", default, deserialize_with = "duration_string")]
+    pub read_idle_timeout: Option<String>,
+    #[serde(rename = "pingTimeout", default, deserialize_with = "duration_string")]
+    pub ping_timeout: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TcpServersTransport {
+    pub name: String,
+    #[serde(default)]
+    pub provider: String,
+    #[serde(rename = "dialTimeout", default, deserialize_with = "duration_string")]
+    pub dial_timeout: Option<String>,
+    #[serde(rename = "dialKeepAlive", default, deserialize_with = "duration_string")]
+    pub dial_keep_alive: Option<String>,
+    #[serde(rename = "terminationDelay", default, deserialize_with = "duration_string")]
+    pub termination_delay: Option<String>,
+    pub tls: Option<TcpTransportTls>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TcpTransportTls {
+    #[serde(rename = "serverName")]
+    pub server_name: Option<String>,
+    #[serde(rename = "insecureSkipVerify")]
+    pub insecure_skip_verify: Option<bool>,
+    #[serde(rename = "rootCAs")]
+    pub root_cas: Option<Vec<String>>,
+    pub certificates: Option<Vec<TransportCertificate>>,
+    #[serde(rename = "peerCertURI")]
+    pub peer_cert_uri: Option<String>,
+}
+
+/// An API entry that could not be parsed and was left out of the snapshot
+#[derive(Debug, Clone)]
+pub struct ParseWarning {
+    pub kind: String,
+    pub name: String,
+    pub message: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct TraefikData {
     pub routers: Vec<Router>,
     pub services: Vec<Service>,
+    pub middlewares: Vec<Middleware>,
+    /// Whether the middleware list could be fetched, references to middlewares can't be checked otherwise
+    pub middlewares_available: bool,
+    pub entry_points: Vec<EntryPoint>,
+    pub servers_transports: Vec<ServersTransport>,
+    pub tcp_servers_transports: Vec<TcpServersTransport>,
+    pub warnings: Vec<ParseWarning>,
 }
\ No newline at end of file